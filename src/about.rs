@@ -0,0 +1,98 @@
+use crate::{
+    ac, alarm, battery,
+    thresholds::{self, ThresholdKind},
+};
+use std::path::{Path, PathBuf};
+
+// Compact, read-only summary of what batty detected, meant for bug reports
+pub fn run(power_supply_paths: &[PathBuf], bat_paths: &[PathBuf]) {
+    println!("batty {}", env!("CARGO_PKG_VERSION"));
+    println!(
+        "Power supply path: {}",
+        battery::display_paths(power_supply_paths)
+    );
+
+    let names: Vec<_> = bat_paths
+        .iter()
+        .filter_map(|path| path.file_name())
+        .map(|name| name.to_string_lossy())
+        .collect();
+    if names.is_empty() {
+        println!("Batteries: none");
+    } else {
+        println!("Batteries: {} ({})", names.len(), names.join(", "));
+    }
+
+    for (path, name) in bat_paths.iter().zip(&names) {
+        if !battery::is_present(path) {
+            println!("  {}: bay empty", name);
+            continue;
+        }
+        println!(
+            "  {}: source {}, thresholds {}, alarm {}",
+            name,
+            charge_source(path),
+            threshold_scheme(path),
+            if alarm::is_supported(path) {
+                "yes"
+            } else {
+                "no"
+            }
+        );
+    }
+
+    let sources: Vec<_> = power_supply_paths
+        .iter()
+        .flat_map(|path| ac::find_sources(path))
+        .collect();
+    if sources.is_empty() {
+        println!("AC adapters: none");
+    } else {
+        let adapters: Vec<_> = sources
+            .iter()
+            .map(|source| {
+                format!(
+                    "{} ({})",
+                    source.name,
+                    if source.online { "online" } else { "offline" }
+                )
+            })
+            .collect();
+        println!("AC adapters: {}", adapters.join(", "));
+    }
+}
+
+pub fn charge_source(path: &Path) -> &'static str {
+    if path.join("energy_now").exists() {
+        "energy_*"
+    } else if path.join("charge_now").exists() {
+        "charge_*"
+    } else if path.join("capacity").exists() {
+        "capacity only"
+    } else {
+        "none"
+    }
+}
+
+pub fn threshold_scheme(path: &Path) -> String {
+    // Relative to the battery, so files under device/ show where they live
+    let file_name = |kind| {
+        let file = thresholds::get_path_for_kind(path, &kind);
+        file.exists()
+            .then(|| {
+                file.strip_prefix(path)
+                    .ok()
+                    .map(|n| n.display().to_string())
+            })
+            .flatten()
+    };
+
+    match (
+        file_name(ThresholdKind::Start),
+        file_name(ThresholdKind::End),
+    ) {
+        (Some(start), Some(end)) => format!("{} + {}", start, end),
+        (None, Some(end)) => format!("{} (end only)", end),
+        _ => "unsupported".to_string(),
+    }
+}
@@ -0,0 +1,72 @@
+use crate::battery;
+use std::{fs, path::Path};
+
+// USB-C/PD ports show up as "USB" supplies next to the classic "Mains" adapter
+const SOURCE_TYPES: [&str; 2] = ["Mains", "USB"];
+
+#[derive(Clone, PartialEq)]
+pub struct PowerSource {
+    pub name: String,
+    pub online: bool,
+    pub power: Option<u64>,
+}
+
+impl PowerSource {
+    fn read(path: &Path) -> Option<Self> {
+        let supply_type = fs::read_to_string(path.join("type")).ok()?;
+        if !SOURCE_TYPES.contains(&supply_type.trim()) {
+            return None;
+        }
+
+        let online = fs::read_to_string(path.join("online"))
+            .map(|online| online.trim() == "1")
+            .unwrap_or(false);
+        let power = battery::read_power_draw(path).filter(|&p| p > 0);
+
+        Some(Self {
+            name: path.file_name()?.to_string_lossy().into_owned(),
+            online,
+            power,
+        })
+    }
+
+    pub fn power_watts(&self) -> Option<f32> {
+        self.power.map(|p| p as f32 / 1_000_000.0)
+    }
+}
+
+pub fn find_sources(power_supply_path: &Path) -> Vec<PowerSource> {
+    let mut sources: Vec<_> = fs::read_dir(power_supply_path)
+        .ok()
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter_map(|path| PowerSource::read(&path))
+        .collect();
+    sources.sort_by(|a, b| a.name.cmp(&b.name));
+    sources
+}
+
+// The online source delivering the most power is the one actually charging
+pub fn active_source(sources: &[PowerSource]) -> Option<&PowerSource> {
+    sources
+        .iter()
+        .filter(|source| source.online)
+        .max_by_key(|source| source.power.unwrap_or(0))
+}
+
+// e.g. "AC: USBC1 (65W)", "AC: ADP1 (online)" or "AC: offline"
+pub fn summary(sources: &[PowerSource]) -> Option<String> {
+    if sources.is_empty() {
+        return None;
+    }
+
+    Some(match active_source(sources) {
+        Some(source) => match source.power_watts() {
+            Some(watts) => format!("AC: {} ({:.0}W)", source.name, watts),
+            None => format!("AC: {} (online)", source.name),
+        },
+        None => "AC: offline".to_string(),
+    })
+}
@@ -0,0 +1,41 @@
+use crate::{lock::ThresholdLock, thresholds};
+use std::{fs, io, path::Path};
+
+const ALARM_FILE: &str = "alarm";
+
+// The alarm is stored in the same unit as energy_now/charge_now (µWh or µAh),
+// so it is shown and set as a percentage of the battery's full capacity
+fn full_capacity(bat_path: &Path) -> io::Result<u64> {
+    let raw = fs::read_to_string(bat_path.join("energy_full"))
+        .or_else(|_| fs::read_to_string(bat_path.join("charge_full")))?;
+    match raw.trim().parse::<u64>() {
+        Ok(full) if full > 0 => Ok(full),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid full capacity value: {}", raw.trim()),
+        )),
+    }
+}
+
+pub fn is_supported(bat_path: &Path) -> bool {
+    bat_path.join(ALARM_FILE).exists()
+}
+
+pub fn read(bat_path: &Path) -> io::Result<u8> {
+    let raw = fs::read_to_string(bat_path.join(ALARM_FILE))?;
+    let value: u64 = raw.trim().parse().map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid alarm value: {}", raw.trim()),
+        )
+    })?;
+    let full = full_capacity(bat_path)?;
+    Ok(((value * 100 + full / 2) / full).min(100) as u8)
+}
+
+pub fn write(bat_path: &Path, percent: u8) -> io::Result<()> {
+    let full = full_capacity(bat_path)?;
+    let raw = (full * u64::from(percent) / 100).to_string();
+    let _lock = ThresholdLock::acquire()?;
+    thresholds::write_attribute(&bat_path.join(ALARM_FILE), &raw).map_err(io::Error::from)
+}
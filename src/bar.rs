@@ -0,0 +1,92 @@
+use crate::{
+    battery::Battery,
+    color::{self, Color},
+    signals,
+};
+use crossterm::{cursor, execute, terminal};
+use std::{
+    io::{self, IsTerminal, Write},
+    path::Path,
+    time::Duration,
+};
+
+// Bars narrower than this are unreadable, wider ones are hard to scan
+const MIN_BAR_WIDTH: usize = 10;
+const MAX_BAR_WIDTH: usize = 50;
+
+pub fn print(battery_path: &Path) -> Result<(), String> {
+    let (battery, _) =
+        Battery::new(battery_path).map_err(|e| format!("Failed to read battery: {}", e))?;
+    println!("{}", render(&battery, terminal_width()));
+    Ok(())
+}
+
+// Redraws the bar in place until interrupted; the width is queried on every
+// refresh so resizing the terminal is picked up
+pub fn follow(battery_path: &Path, interval: Duration) -> Result<(), String> {
+    let mut stdout = io::stdout();
+    let interactive = stdout.is_terminal();
+    if interactive {
+        let _ = execute!(stdout, cursor::Hide);
+    }
+
+    let result = loop {
+        let line = match Battery::new(battery_path) {
+            Ok((battery, _)) => render(&battery, terminal_width()),
+            Err(e) => break Err(format!("Failed to read battery: {}", e)),
+        };
+        // \x1b[K clears whatever a longer previous line left behind
+        print!("\r{}\x1b[K", line);
+        let _ = stdout.flush();
+
+        if !signals::sleep(interval) {
+            break Ok(());
+        }
+    };
+
+    if interactive {
+        let _ = execute!(stdout, cursor::Show);
+    }
+    println!();
+    result
+}
+
+fn terminal_width() -> usize {
+    if !io::stdout().is_terminal() {
+        return 80;
+    }
+    terminal::size()
+        .map(|(columns, _)| usize::from(columns))
+        .unwrap_or(80)
+}
+
+fn render(battery: &Battery, columns: usize) -> String {
+    let percentage = battery.percentage();
+    let label = percentage
+        .map(|p| format!("{:.0}%", p))
+        .unwrap_or_else(|| "?".to_string());
+    let suffix = format!(" {} {}", label, battery.status.label());
+    let prefix = format!("{} ", battery.name());
+
+    // Leave the last column free so the line never wraps
+    let width = columns
+        .saturating_sub(prefix.chars().count() + suffix.chars().count() + 3)
+        .clamp(MIN_BAR_WIDTH, MAX_BAR_WIDTH);
+    let filled = percentage
+        .map(|p| (p / 100.0 * width as f32).round() as usize)
+        .unwrap_or(0)
+        .min(width);
+    let bar = "█".repeat(filled);
+    let bar = match percentage {
+        Some(p) => color::paint(&bar, Color::for_percentage(p)),
+        None => bar,
+    };
+
+    format!(
+        "{}[{}{}]{}",
+        prefix,
+        bar,
+        "░".repeat(width - filled),
+        suffix
+    )
+}
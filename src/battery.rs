@@ -1,13 +1,26 @@
+use crate::{
+    error::BattyError,
+    i18n::{tr, tr_args, Msg},
+    thresholds,
+};
+use clap::ValueEnum;
+use serde::Deserialize;
 use std::{
-    fmt, fs, io,
+    collections::BTreeMap,
+    env, fmt, fs, io,
     path::{Path, PathBuf},
     str::FromStr,
+    sync::OnceLock,
+    thread,
+    time::Duration,
 };
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum BatteryStatus {
     Charging,
+    Discharging,
     NotCharging,
+    Full,
     Unknown,
 }
 
@@ -15,28 +28,117 @@ impl BatteryStatus {
     pub fn as_str(&self) -> &'static str {
         match self {
             Self::Charging => "charging",
+            Self::Discharging => "discharging",
             Self::NotCharging => "not charging",
+            Self::Full => "full",
             Self::Unknown => "unknown",
         }
     }
+
+    // Localized form for human-readable output; as_str() stays fixed for JSON/CSV
+    pub fn label(&self) -> &'static str {
+        tr(match self {
+            Self::Charging => Msg::Charging,
+            Self::Discharging => Msg::Discharging,
+            Self::NotCharging => Msg::NotCharging,
+            Self::Full => Msg::Full,
+            Self::Unknown => Msg::Unknown,
+        })
+    }
+
+    pub fn icon(&self) -> char {
+        match self {
+            Self::Charging => '⚡',
+            Self::Discharging => '🔋',
+            Self::NotCharging => '⏸',
+            Self::Full => '✓',
+            Self::Unknown => '?',
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Severity {
+    Info,
+    Warning,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Warning {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Warning {
+    pub fn info(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Info,
+            message: message.into(),
+        }
+    }
+
+    pub fn warn(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
 }
 
 pub enum BatteryAttribute {
     CurrPower,
     TotalPower,
+    DesignPower,
+    CurrCharge,
+    TotalCharge,
+    DesignCharge,
+    Capacity,
     Status,
     Cycles,
+    PowerNow,
+    CurrentNow,
+    VoltageNow,
+    Temperature,
+    Present,
+    SerialNumber,
 }
 
 impl BatteryAttribute {
-    fn file_name(&self) -> &'static str {
+    // Candidate sysfs file names in order of preference. Drivers differ in
+    // which of these they expose, so the first one that exists is read:
+    // - energy_*/charge_*: instantaneous value first, then the averaged
+    //   *_avg variant some smart-battery (SBS) drivers provide instead
+    // - cycle_count: ACPI and most vendor drivers
+    fn file_names(&self) -> &'static [&'static str] {
         match self {
-            Self::CurrPower => "energy_now",
-            Self::TotalPower => "energy_full",
-            Self::Status => "status",
-            Self::Cycles => "cycle_count",
+            Self::CurrPower => &["energy_now", "energy_avg"],
+            Self::TotalPower => &["energy_full"],
+            Self::DesignPower => &["energy_full_design"],
+            Self::CurrCharge => &["charge_now", "charge_avg"],
+            Self::TotalCharge => &["charge_full"],
+            Self::DesignCharge => &["charge_full_design"],
+            Self::Capacity => &["capacity"],
+            Self::Status => &["status"],
+            Self::Cycles => &["cycle_count"],
+            Self::PowerNow => &["power_now", "power_avg"],
+            Self::CurrentNow => &["current_now", "current_avg"],
+            Self::VoltageNow => &["voltage_now", "voltage_avg"],
+            Self::Temperature => &["temp"],
+            Self::Present => &["present"],
+            Self::SerialNumber => &["serial_number"],
         }
     }
+
+    fn resolve(&self, bat_path: &Path) -> PathBuf {
+        let candidates = self.file_names();
+        first_existing(bat_path, candidates).unwrap_or_else(|| bat_path.join(candidates[0]))
+    }
 }
 
 impl fmt::Display for BatteryAttribute {
@@ -44,131 +146,968 @@ impl fmt::Display for BatteryAttribute {
         match self {
             Self::CurrPower => write!(f, "current power"),
             Self::TotalPower => write!(f, "total power"),
+            Self::DesignPower => write!(f, "design power"),
+            Self::CurrCharge => write!(f, "current charge"),
+            Self::TotalCharge => write!(f, "total charge"),
+            Self::DesignCharge => write!(f, "design charge"),
+            Self::Capacity => write!(f, "capacity"),
             Self::Status => write!(f, "status"),
             Self::Cycles => write!(f, "cycle count"),
+            Self::PowerNow => write!(f, "power draw"),
+            Self::CurrentNow => write!(f, "current"),
+            Self::VoltageNow => write!(f, "voltage"),
+            Self::Temperature => write!(f, "temperature"),
+            Self::Present => write!(f, "present"),
+            Self::SerialNumber => write!(f, "serial number"),
         }
     }
 }
 
+#[derive(Clone, PartialEq)]
 pub struct Battery {
     path: PathBuf,
-    pub total_power: u32,
-    pub curr_power: u32,
+    pub total_power: Option<u32>,
+    pub curr_power: Option<u32>,
+    pub design_power: Option<u32>,
+    pub capacity: Option<u8>,
     pub status: BatteryStatus,
-    pub cycles: Option<u8>,
+    pub cycles: Option<u32>,
+    pub power: Option<u64>,
+    pub temperature: Option<f32>,
+    pub serial_number: Option<String>,
+    pub present: bool,
+    // Uninterruptible power supply rather than a laptop battery
+    pub ups: bool,
+    // sysfs energy/power units per Wh/W (1e6 normally, 1e3 for mWh drivers)
+    units_per_wh: f32,
+    // curr_power/total_power hold energy (not charge) readings
+    from_energy: bool,
 }
 
 impl Battery {
-    pub fn new(path: &Path) -> io::Result<(Self, Vec<String>)> {
+    pub fn new(path: &Path) -> Result<(Self, Vec<Warning>), BattyError> {
+        // An AC adapter passed via --path would otherwise read as a battery
+        // with every attribute missing
+        if let Ok(kind) = fs::read_to_string(path.join("type")) {
+            let kind = kind.trim();
+            if !kind.eq_ignore_ascii_case("battery") && !kind.eq_ignore_ascii_case("ups") {
+                return Err(BattyError::NotABattery(path.to_path_buf()));
+            }
+        }
+        if !is_present(path) {
+            return Ok((Self::empty_bay(path), Vec::new()));
+        }
+
         let mut warnings = Vec::new();
         let battery_name = path
             .file_name()
             .and_then(|n| n.to_str())
             .unwrap_or("unknown");
 
-        let curr_power: u32 = read_num_battery_attribute(path, BatteryAttribute::CurrPower)
-            .map_err(|e| {
-                io::Error::new(
-                    e.kind(),
-                    format!(
+        // Prefer energy_* files, then charge_*, and finally let percentage()
+        // fall back to the driver-reported capacity.
+        let mut from_energy = false;
+        let (curr_power, total_power, design_power) = match read_power_pair(
+            path,
+            BatteryAttribute::CurrPower,
+            BatteryAttribute::TotalPower,
+        ) {
+            Ok((curr, total)) => {
+                from_energy = true;
+                (
+                    Some(curr),
+                    Some(total),
+                    read_num_battery_attribute(path, BatteryAttribute::DesignPower).ok(),
+                )
+            }
+            Err(energy_err) => match read_power_pair(
+                path,
+                BatteryAttribute::CurrCharge,
+                BatteryAttribute::TotalCharge,
+            ) {
+                Ok((curr, total)) => {
+                    warnings.push(Warning::info(format!(
+                        "Using charge_now/charge_full for {}: energy files unavailable.",
+                        battery_name
+                    )));
+                    (
+                        Some(curr),
+                        Some(total),
+                        read_num_battery_attribute(path, BatteryAttribute::DesignCharge).ok(),
+                    )
+                }
+                // UPSes usually only report capacity, so that's not worth a warning
+                Err(_) if is_ups(path) => (None, None, None),
+                // Minimal drivers (some ARM boards) only expose capacity and status;
+                // percentage() uses capacity and power/health stay unknown
+                Err(charge_err)
+                    if energy_err.is_missing()
+                        && charge_err.is_missing()
+                        && BatteryAttribute::Capacity.resolve(path).exists() =>
+                {
+                    warnings.push(Warning::info(format!(
+                        "{} only reports capacity and status; power and health are unknown.",
+                        battery_name
+                    )));
+                    (None, None, None)
+                }
+                Err(_) => {
+                    warnings.push(Warning::warn(format!(
                         "Failed to read {} for {}: {}",
                         BatteryAttribute::CurrPower,
                         battery_name,
-                        e
-                    ),
-                )
-            })?;
+                        energy_err
+                    )));
+                    (None, None, None)
+                }
+            },
+        };
 
-        let total_power: u32 = read_num_battery_attribute(path, BatteryAttribute::TotalPower)
-            .map_err(|e| {
-                io::Error::new(
-                    e.kind(),
-                    format!(
-                        "Failed to read {} for {}: {}",
-                        BatteryAttribute::TotalPower,
-                        battery_name,
-                        e
-                    ),
-                )
-            })?;
-
-        let status = read_str_battery_attribute(path, BatteryAttribute::Status)
-            .map(
-                |status_str| match status_str.trim().to_lowercase().as_str() {
-                    "charging" => BatteryStatus::Charging,
-                    _ => BatteryStatus::NotCharging,
-                },
-            )
+        // Right after a recalibration the current charge can briefly exceed the
+        // learned full capacity; percentage() clamps it
+        if let (Some(curr), Some(total)) = (curr_power, total_power) {
+            if total > 0 && curr > total {
+                warnings.push(Warning::warn(format!(
+                    "{} reports more charge than its full capacity ({:.1}%); showing 100%.",
+                    battery_name,
+                    curr as f32 / total as f32 * 100.0
+                )));
+            }
+        }
+
+        // A corrupt design capacity would turn into a health of 0% or 800%
+        let design_power = match (total_power, design_power) {
+            (Some(full), Some(design)) if full > 0 && !is_plausible_design(full, design) => {
+                warnings.push(Warning::warn(format!(
+                    "{} design capacity looks corrupt ({} vs. {} full); health is unknown.",
+                    battery_name, design, full
+                )));
+                None
+            }
+            (_, design) => design,
+        };
+
+        // Only energy_full says anything about the energy/power scale
+        let energy_full =
+            read_num_battery_attribute::<u64>(path, BatteryAttribute::TotalPower).ok();
+        let units_per_wh = units_per_wh(energy_full);
+
+        let capacity: Option<u8> =
+            read_num_battery_attribute(path, BatteryAttribute::Capacity).ok();
+
+        let status = read_str_battery_attribute(path, BatteryAttribute::Status, &mut warnings)
+            .and_then(|status_str| {
+                parse_status(&BatteryAttribute::Status.resolve(path), &status_str)
+            })
             .unwrap_or_else(|e| {
-                warnings.push(format!(
+                warnings.push(Warning::warn(format!(
                     "Failed to read status for {}: {}. Using 'unknown'.",
                     battery_name, e
-                ));
+                )));
                 BatteryStatus::Unknown
             });
 
-        let cycles: Option<u8> = read_num_battery_attribute(path, BatteryAttribute::Cycles).ok();
+        // A missing file just means no cycle counter; garbage in it (e.g. 0xFFFF
+        // or an empty string) is a firmware bug worth pointing out. Failing to
+        // read it at all (permissions, I/O) is only a warning, since the cycle
+        // count is optional.
+        let cycles = match read_num_battery_attribute::<u32>(path, BatteryAttribute::Cycles) {
+            Ok(cycles) => Some(cycles),
+            Err(e) if e.is_missing() => None,
+            Err(BattyError::AttributeUnparseable { .. }) => {
+                warnings.push(Warning::warn(format!(
+                    "{} reports an invalid cycle_count; the firmware may be buggy.",
+                    battery_name
+                )));
+                None
+            }
+            Err(e) => {
+                warnings.push(Warning::warn(format!(
+                    "Failed to read {} for {}: {}",
+                    BatteryAttribute::Cycles,
+                    battery_name,
+                    e
+                )));
+                None
+            }
+        };
+        let power = read_power_draw(path);
+        // temp is reported in tenths of a degree Celsius
+        let temperature = read_num_battery_attribute::<i32>(path, BatteryAttribute::Temperature)
+            .ok()
+            .map(|t| t as f32 / 10.0);
+        // Many drivers leave the file empty rather than omitting it
+        let serial_number =
+            read_str_battery_attribute(path, BatteryAttribute::SerialNumber, &mut warnings)
+                .ok()
+                .map(|serial| serial.trim().to_string())
+                .filter(|serial| !serial.is_empty());
         Ok((
             Self {
                 path: path.to_path_buf(),
                 curr_power,
                 total_power,
+                design_power,
+                capacity,
                 status,
                 cycles,
+                power,
+                temperature,
+                serial_number,
+                present: true,
+                ups: is_ups(path),
+                units_per_wh,
+                from_energy,
             },
             warnings,
         ))
     }
 
-    pub fn refresh(&mut self) -> io::Result<Vec<String>> {
+    fn empty_bay(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            total_power: None,
+            curr_power: None,
+            design_power: None,
+            capacity: None,
+            status: BatteryStatus::Unknown,
+            cycles: None,
+            power: None,
+            temperature: None,
+            serial_number: None,
+            present: false,
+            ups: is_ups(path),
+            units_per_wh: units_per_wh(None),
+            from_energy: false,
+        }
+    }
+
+    pub fn refresh(&mut self) -> Result<Vec<Warning>, BattyError> {
         let (battery, warnings) = Self::new(&self.path)?;
         *self = battery;
         Ok(warnings)
     }
 
-    pub fn percentage(&self) -> f32 {
-        (self.curr_power as f32 / self.total_power as f32) * 100.0
+    pub fn power_watts(&self) -> Option<f32> {
+        self.power.map(|p| p as f32 / self.units_per_wh)
+    }
+
+    // (now, full) in Wh; None when only charge (µAh) readings are available
+    pub fn energy_wh(&self) -> Option<(f32, f32)> {
+        if !self.from_energy {
+            return None;
+        }
+        let curr = self.curr_power? as f32 / self.units_per_wh;
+        let total = self.total_power? as f32 / self.units_per_wh;
+        Some((curr, total))
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.status == BatteryStatus::Full || self.percentage().is_some_and(|p| p >= 100.0)
     }
+
+    pub fn name(&self) -> &str {
+        self.path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown")
+    }
+
+    pub fn health(&self) -> Option<f32> {
+        match (self.total_power, self.design_power) {
+            (Some(total), Some(design)) if design > 0 => {
+                Some((total as f32 / design as f32) * 100.0)
+            }
+            _ => None,
+        }
+    }
+
+    // Needs both the cycle count and the health; either one missing gives Unknown
+    pub fn wear_estimate(&self, cutoffs: &WearCutoffs) -> Wear {
+        let (Some(cycles), Some(health)) = (self.cycles, self.health()) else {
+            return Wear::Unknown;
+        };
+        if health < f32::from(cutoffs.worn_health) || cycles >= cutoffs.worn_cycles {
+            Wear::Worn
+        } else if health < f32::from(cutoffs.fair_health) || cycles >= cutoffs.fair_cycles {
+            Wear::Fair
+        } else {
+            Wear::Good
+        }
+    }
+
+    pub fn percentage(&self) -> Option<f32> {
+        match (self.curr_power, self.total_power) {
+            (Some(curr), Some(total)) if total > 0 => {
+                Some(((curr as f32 / total as f32) * 100.0).min(100.0))
+            }
+            _ => self.capacity.map(|capacity| f32::from(capacity.min(100))),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Rounding {
+    #[default]
+    Exact,
+    Floor,
+    Ceil,
+    Snap,
+}
+
+impl Rounding {
+    // Snap shows the end threshold once the charge is within 1% of it, since the
+    // hardware already treats the battery as charged at that point
+    pub fn format(self, percentage: f32, end: Option<u8>) -> String {
+        match self {
+            Rounding::Exact => format!("{:.2}%", percentage),
+            Rounding::Floor => format!("{}%", percentage.floor()),
+            Rounding::Ceil => format!("{}%", percentage.ceil()),
+            Rounding::Snap => match end {
+                Some(end) if (percentage - f32::from(end)).abs() <= 1.0 => format!("{}%", end),
+                _ => format!("{:.2}%", percentage),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Wear {
+    Good,
+    Fair,
+    Worn,
+    Unknown,
+}
+
+impl Wear {
+    pub fn label(self) -> &'static str {
+        tr(match self {
+            Self::Good => Msg::WearGood,
+            Self::Fair => Msg::WearFair,
+            Self::Worn => Msg::WearWorn,
+            Self::Unknown => Msg::Unknown,
+        })
+    }
+
+    pub fn advice(self) -> Option<&'static str> {
+        match self {
+            Self::Fair => Some(
+                "Capacity is declining; a lower end threshold (e.g. --longevity) slows further wear.",
+            ),
+            Self::Worn => Some("The battery is worn; consider replacing it."),
+            Self::Good | Self::Unknown => None,
+        }
+    }
+}
+
+// A battery is Fair below either fair cutoff and Worn below either worn one.
+// Health is a percentage of the design capacity.
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WearCutoffs {
+    pub fair_health: u8,
+    pub worn_health: u8,
+    pub fair_cycles: u32,
+    pub worn_cycles: u32,
+}
+
+impl Default for WearCutoffs {
+    fn default() -> Self {
+        Self {
+            fair_health: 90,
+            worn_health: 80,
+            fair_cycles: 300,
+            worn_cycles: 800,
+        }
+    }
+}
+
+// Worn batteries often report Full well below 100%, which reads like a fault
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FullDisplay {
+    // The computed percentage, unchanged
+    Exact,
+    // Always 100%
+    Hundred,
+    // "Full (97.12%)"
+    #[default]
+    Annotate,
+}
+
+impl FullDisplay {
+    pub fn format(self, battery: &Battery, percentage: f32, text: String) -> String {
+        if battery.status != BatteryStatus::Full || percentage >= 100.0 {
+            return text;
+        }
+        match self {
+            FullDisplay::Exact => text,
+            FullDisplay::Hundred => "100%".to_string(),
+            FullDisplay::Annotate => tr_args(Msg::FullAnnotated, &[&text]),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnergyScale {
+    #[default]
+    Auto,
+    Uwh,
+    Mwh,
+}
+
+static ENERGY_SCALE: OnceLock<EnergyScale> = OnceLock::new();
+
+// Below 1 Wh in µWh no laptop battery is plausible, so such values are mWh
+const MIN_PLAUSIBLE_UWH: u64 = 1_000_000;
+
+pub fn set_energy_scale(scale: EnergyScale) {
+    let _ = ENERGY_SCALE.set(scale);
+}
+
+// The kernel ABI says µWh/µW, but some embedded drivers report mWh/mW. Ratios
+// like the percentage don't care; absolute Wh/W values do.
+pub fn units_per_wh(energy_full: Option<u64>) -> f32 {
+    match ENERGY_SCALE.get().copied().unwrap_or_default() {
+        EnergyScale::Uwh => 1_000_000.0,
+        EnergyScale::Mwh => 1_000.0,
+        EnergyScale::Auto => match energy_full {
+            Some(full) if (1_000..MIN_PLAUSIBLE_UWH).contains(&full) => 1_000.0,
+            _ => 1_000_000.0,
+        },
+    }
+}
+
+pub struct BatterySnapshot {
+    pub capacity: Option<u8>,
+    pub status: BatteryStatus,
+    pub present: bool,
 }
 
-pub fn find_batteries(power_supply_path: &PathBuf) -> Vec<PathBuf> {
-    fs::read_dir(power_supply_path)
+impl BatterySnapshot {
+    // Only reads capacity and status so it stays cheap for background tabs
+    pub fn read(path: &Path) -> Self {
+        let present = is_present(path);
+        let capacity = read_num_battery_attribute(path, BatteryAttribute::Capacity).ok();
+        let status = read_str_battery_attribute(path, BatteryAttribute::Status, &mut Vec::new())
+            .and_then(|status_str| {
+                parse_status(&BatteryAttribute::Status.resolve(path), &status_str)
+            })
+            .unwrap_or(BatteryStatus::Unknown);
+
+        Self {
+            capacity,
+            status,
+            present,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        if !self.present {
+            return tr(Msg::Empty).to_string();
+        }
+        match (&self.status, self.capacity) {
+            (BatteryStatus::Full, _) | (_, Some(100)) => BatteryStatus::Full.icon().to_string(),
+            (_, Some(capacity)) => format!("{}%", capacity),
+            (_, None) => "?".to_string(),
+        }
+    }
+}
+
+// Modular bays keep the directory around with present=0 when the battery is
+// pulled; drivers without the file only list batteries that are there
+pub fn is_present(path: &Path) -> bool {
+    read_num_battery_attribute::<u8>(path, BatteryAttribute::Present)
+        .map(|present| present != 0)
+        .unwrap_or(true)
+}
+
+pub fn is_battery_dir(path: &Path) -> bool {
+    let is_battery_type = fs::read_to_string(path.join("type"))
+        .map(|t| t.trim().eq_ignore_ascii_case("battery"))
+        .unwrap_or(false);
+
+    is_battery_type || thresholds::is_supported(path)
+}
+
+pub fn is_ups(path: &Path) -> bool {
+    fs::read_to_string(path.join("type"))
+        .map(|t| t.trim().eq_ignore_ascii_case("ups"))
+        .unwrap_or(false)
+}
+
+// Wireless mice, keyboards and headsets report scope "Device"; the laptop's
+// own batteries say "System" or have no scope file at all
+pub fn is_peripheral(path: &Path) -> bool {
+    fs::read_to_string(path.join("scope"))
+        .map(|scope| scope.trim().eq_ignore_ascii_case("device"))
+        .unwrap_or(false)
+}
+
+// --path wins over BATTY_POWER_SUPPLY_PATH, which wins over the sysfs default.
+// Both take several roots, the variable colon-separated like PATH.
+pub const DEFAULT_POWER_SUPPLY_PATH: &str = "/sys/class/power_supply";
+
+pub fn power_supply_paths(paths: Vec<PathBuf>) -> Vec<PathBuf> {
+    if !paths.is_empty() {
+        return paths;
+    }
+    let from_env: Vec<_> = env::var_os("BATTY_POWER_SUPPLY_PATH")
+        .map(|value| {
+            env::split_paths(&value)
+                .filter(|p| !p.as_os_str().is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    if from_env.is_empty() {
+        vec![PathBuf::from(DEFAULT_POWER_SUPPLY_PATH)]
+    } else {
+        from_env
+    }
+}
+
+// For messages naming every root, e.g. "No batteries found in /a:/b"
+pub fn display_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+// A typo in --path would otherwise surface as "no batteries found"
+pub fn check_power_supply_path(path: &Path) -> Result<(), String> {
+    match fs::metadata(path) {
+        Ok(metadata) if metadata.is_dir() => Ok(()),
+        Ok(_) => Err(format!(
+            "power supply path {} is not a directory",
+            path.display()
+        )),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Err(format!(
+            "power supply path {} does not exist",
+            path.display()
+        )),
+        Err(e) => Err(format!(
+            "cannot access power supply path {}: {}",
+            path.display(),
+            e
+        )),
+    }
+}
+
+// UPSes are recognized by their type since their names vary by driver, and
+// Device-scoped supplies are left out unless peripherals are asked for.
+// sysfs entries are symlinks into the device tree. Each one is resolved to
+// check it leads to a directory, but the class path is kept since the AC
+// adapters are looked up next to it.
+//
+// A root may also be a battery directory itself (e.g. .../power_supply/BAT0).
+// Roots are scanned in order and a battery reachable from several of them
+// (say the live tree and a bind mount of it) is listed once, from the first.
+pub fn find_batteries(
+    power_supply_paths: &[PathBuf],
+    include_ups: bool,
+    include_peripherals: bool,
+) -> (Vec<PathBuf>, Vec<Warning>) {
+    let mut batteries = Vec::new();
+    let mut targets = Vec::new();
+    let mut warnings = Vec::new();
+
+    for root in power_supply_paths {
+        let candidates: Vec<PathBuf> = if is_battery_dir(root) || (include_ups && is_ups(root)) {
+            vec![root.clone()]
+        } else {
+            fs::read_dir(root)
+                .ok()
+                .into_iter()
+                .flatten()
+                .filter_map(Result::ok)
+                .filter(|entry| {
+                    let path = entry.path();
+                    let is_battery = entry
+                        .file_name()
+                        .to_str()
+                        .map(|name| name.starts_with("BAT"))
+                        .unwrap_or(false);
+                    let is_peripheral = is_peripheral(&path);
+                    if is_peripheral && !include_peripherals {
+                        return false;
+                    }
+                    is_battery
+                        || (include_ups && is_ups(&path))
+                        || (is_peripheral && is_battery_dir(&path))
+                })
+                .map(|entry| entry.path())
+                .collect()
+        };
+
+        for path in candidates {
+            match fs::canonicalize(&path) {
+                Ok(target) if target.is_dir() => {
+                    if !targets.contains(&target) {
+                        targets.push(target);
+                        batteries.push(path);
+                    }
+                }
+                Ok(target) => warnings.push(Warning::warn(format!(
+                    "Skipping {}: {} is not a directory",
+                    path.display(),
+                    target.display()
+                ))),
+                Err(e) => warnings.push(Warning::warn(format!(
+                    "Skipping {}: cannot resolve the link: {}",
+                    path.display(),
+                    e
+                ))),
+            }
+        }
+    }
+    (batteries, warnings)
+}
+
+// The design capacity should be at least the learned full capacity; a design
+// below full, or more than 3x full, is a bad reading rather than a dead battery
+const MAX_DESIGN_TO_FULL: u64 = 3;
+
+fn is_plausible_design(full: u32, design: u32) -> bool {
+    design >= full && u64::from(design) <= u64::from(full) * MAX_DESIGN_TO_FULL
+}
+
+// Every readable file in the battery directory, for the export bundle and
+// the TUI's attribute pane. Unreadable files are kept with the error.
+pub fn read_attributes(path: &Path) -> BTreeMap<String, String> {
+    let mut attributes = BTreeMap::new();
+    let Ok(entries) = fs::read_dir(path) else {
+        return attributes;
+    };
+
+    for entry in entries.flatten() {
+        let file = entry.path();
+        // Skip subdirectories (power/, hwmon*/) and links back to the device
+        if !entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+            continue;
+        }
+        let value = match read_with_retry(&file) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).trim().to_string(),
+            Err(e) => format!("<unreadable: {}>", e),
+        };
+        attributes.insert(entry.file_name().to_string_lossy().into_owned(), value);
+    }
+    attributes
+}
+
+pub fn first_existing(dir: &Path, candidates: &[&str]) -> Option<PathBuf> {
+    candidates
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.exists())
+}
+
+// The values the kernel documents for the status attribute; anything else is
+// an error rather than a guess, so callers report it and show "unknown"
+fn parse_status(path: &Path, status_str: &str) -> Result<BatteryStatus, BattyError> {
+    match status_str.trim().to_lowercase().as_str() {
+        "charging" => Ok(BatteryStatus::Charging),
+        "discharging" => Ok(BatteryStatus::Discharging),
+        "not charging" => Ok(BatteryStatus::NotCharging),
+        "full" => Ok(BatteryStatus::Full),
+        "unknown" => Ok(BatteryStatus::Unknown),
+        _ => Err(BattyError::unparseable(path, status_str.trim())),
+    }
+}
+
+// power_now in µW, or current_now (µA) × voltage_now (µV) when the driver only reports those
+pub fn read_power_draw(bat_path: &Path) -> Option<u64> {
+    read_num_battery_attribute::<i64>(bat_path, BatteryAttribute::PowerNow)
+        .map(|p| p.unsigned_abs())
         .ok()
-        .into_iter()
-        .flatten()
-        .filter_map(Result::ok)
-        .filter(|entry| {
-            entry
-                .file_name()
-                .to_str()
-                .map(|name| name.starts_with("BAT"))
-                .unwrap_or(false)
+        .or_else(|| {
+            let current =
+                read_num_battery_attribute::<i64>(bat_path, BatteryAttribute::CurrentNow).ok()?;
+            let voltage =
+                read_num_battery_attribute::<i64>(bat_path, BatteryAttribute::VoltageNow).ok()?;
+            Some(current.unsigned_abs() * voltage.unsigned_abs() / 1_000_000)
         })
-        .map(|entry| entry.path())
-        .collect()
 }
 
-fn read_num_battery_attribute<T>(bat_path: &Path, attr: BatteryAttribute) -> io::Result<T>
-where
-    T: FromStr,
-    <T as FromStr>::Err: std::fmt::Display,
-{
-    let val = read_str_battery_attribute(bat_path, attr)?;
+fn read_power_pair(
+    bat_path: &Path,
+    curr_attr: BatteryAttribute,
+    total_attr: BatteryAttribute,
+) -> Result<(u32, u32), BattyError> {
+    let curr = read_num_battery_attribute(bat_path, curr_attr)?;
+    let total = read_num_battery_attribute(bat_path, total_attr)?;
+    Ok((curr, total))
+}
+
+fn read_num_battery_attribute<T: FromStr>(
+    bat_path: &Path,
+    attr: BatteryAttribute,
+) -> Result<T, BattyError> {
+    let path = attr.resolve(bat_path);
+    // Invalid bytes in a number fail the parse below, so the lossy warning adds nothing
+    let val = read_str_battery_attribute(bat_path, attr, &mut Vec::new())?;
     let trimmed = val.trim();
-    trimmed.parse::<T>().map_err(|e| {
-        io::Error::new(
-            io::ErrorKind::InvalidData,
-            format!("invalid battery attribute value: {} ({})", trimmed, e),
-        )
-    })
-}
-
-fn read_str_battery_attribute(bat_path: &Path, attr: BatteryAttribute) -> io::Result<String> {
-    let path = bat_path.join(attr.file_name());
-    fs::read_to_string(&path).map_err(|e| {
-        io::Error::new(
-            e.kind(),
-            format!("Failed to read {}: {}", path.display(), e),
-        )
-    })
+    trimmed
+        .parse::<T>()
+        .map_err(|_| BattyError::unparseable(&path, trimmed))
+}
+
+// sysfs reads can briefly fail with EBUSY/EAGAIN while ACPI is busy
+const READ_RETRIES: u32 = 2;
+const READ_RETRY_DELAY: Duration = Duration::from_millis(10);
+
+fn is_transient(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::ResourceBusy | io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted
+    )
+}
+
+// Retries transient failures with a doubling delay; anything else (missing
+// file, permissions) is returned immediately
+pub fn read_with_retry(path: &Path) -> io::Result<Vec<u8>> {
+    let mut delay = READ_RETRY_DELAY;
+    let mut attempt = 0;
+    loop {
+        match fs::read(path) {
+            Err(e) if attempt < READ_RETRIES && is_transient(&e) => {
+                thread::sleep(delay);
+                delay *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+fn read_str_battery_attribute(
+    bat_path: &Path,
+    attr: BatteryAttribute,
+    warnings: &mut Vec<Warning>,
+) -> Result<String, BattyError> {
+    let path = attr.resolve(bat_path);
+    let bytes = read_with_retry(&path).map_err(|e| BattyError::from_io(&path, e))?;
+
+    match String::from_utf8(bytes) {
+        Ok(value) => Ok(value),
+        Err(e) => {
+            warnings.push(Warning::warn(format!(
+                "{} contains invalid UTF-8; decoded it lossily.",
+                path.display()
+            )));
+            Ok(String::from_utf8_lossy(e.as_bytes()).into_owned())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::MockTree;
+    use std::os::unix::fs::symlink;
+
+    fn energy(tree: &MockTree, name: &str, extra: &[(&str, &str)]) -> PathBuf {
+        let mut attributes = vec![
+            ("energy_now", "30000000"),
+            ("energy_full", "50000000"),
+            ("energy_full_design", "55000000"),
+            ("status", "Discharging"),
+        ];
+        attributes.extend_from_slice(extra);
+        tree.battery(name, &attributes)
+    }
+
+    #[test]
+    fn cycle_count_above_255() {
+        let tree = MockTree::new();
+        let path = energy(&tree, "BAT0", &[("cycle_count", "412")]);
+        let (battery, warnings) = Battery::new(&path).unwrap();
+        assert_eq!(battery.cycles, Some(412));
+        assert!(warnings.is_empty());
+    }
+
+    fn health_with_design(design: &str) -> (Option<f32>, Vec<Warning>) {
+        let tree = MockTree::new();
+        let path = energy(&tree, "BAT0", &[("energy_full_design", design)]);
+        let (battery, warnings) = Battery::new(&path).unwrap();
+        (battery.health(), warnings)
+    }
+
+    #[test]
+    fn plausible_design_capacity() {
+        let (health, warnings) = health_with_design("62500000");
+        assert_eq!(health, Some(80.0));
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn implausible_design_capacity() {
+        // Zero, more than 3x full, and below full
+        for design in ["0", "400000000", "45000000"] {
+            let (health, warnings) = health_with_design(design);
+            assert_eq!(health, None, "{}", design);
+            assert_eq!(warnings.len(), 1);
+            assert!(warnings[0]
+                .message
+                .contains("design capacity looks corrupt"));
+        }
+    }
+
+    #[test]
+    fn missing_cycle_count() {
+        let tree = MockTree::new();
+        let path = energy(&tree, "BAT0", &[]);
+        let (battery, warnings) = Battery::new(&path).unwrap();
+        assert_eq!(battery.cycles, None);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn unparseable_cycle_count() {
+        let tree = MockTree::new();
+        for value in ["0xFFFF", "", "99999999999"] {
+            let path = energy(&tree, "BAT0", &[("cycle_count", value)]);
+            let (battery, warnings) = Battery::new(&path).unwrap();
+            assert_eq!(battery.cycles, None);
+            assert_eq!(warnings.len(), 1, "{:?}", value);
+            assert!(warnings[0].message.contains("invalid cycle_count"));
+        }
+    }
+
+    #[test]
+    fn unreadable_cycle_count_is_a_warning() {
+        let tree = MockTree::new();
+        let path = energy(&tree, "BAT0", &[]);
+        // Reading a directory fails with an I/O error rather than bad contents
+        fs::create_dir(path.join("cycle_count")).unwrap();
+        let (battery, warnings) = Battery::new(&path).unwrap();
+        assert_eq!(battery.cycles, None);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0]
+            .message
+            .contains("Failed to read cycle count for BAT0"));
+    }
+
+    #[test]
+    fn overrange_charge_clamps_to_100() {
+        let tree = MockTree::new();
+        let path = energy(&tree, "BAT0", &[("energy_now", "52500000")]);
+        let (battery, warnings) = Battery::new(&path).unwrap();
+        assert_eq!(battery.percentage(), Some(100.0));
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0]
+            .message
+            .contains("more charge than its full capacity (105.0%)"));
+    }
+
+    #[test]
+    fn path_to_a_single_battery() {
+        let tree = MockTree::new();
+        let bat0 = energy(&tree, "BAT0", &[]);
+        energy(&tree, "BAT1", &[]);
+        let (batteries, warnings) = find_batteries(std::slice::from_ref(&bat0), false, false);
+        assert_eq!(batteries, vec![bat0]);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn discovery_skips_broken_links() {
+        let tree = MockTree::new();
+        let root = tree.path();
+        tree.battery("BAT0", &[]);
+        tree.write(root, "not-a-dir", "");
+        symlink(root.join("missing"), root.join("BAT1")).unwrap();
+        symlink(root.join("not-a-dir"), root.join("BAT2")).unwrap();
+
+        let (batteries, warnings) = find_batteries(&[root.to_path_buf()], false, false);
+        assert_eq!(batteries, vec![root.join("BAT0")]);
+        let mut messages: Vec<_> = warnings.iter().map(|w| w.message.as_str()).collect();
+        messages.sort();
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].contains("BAT1") && messages[0].contains("cannot resolve"));
+        assert!(messages[1].contains("BAT2") && messages[1].contains("is not a directory"));
+    }
+
+    #[test]
+    fn discovery_follows_symlinked_batteries() {
+        // Like sysfs: the class directory only holds links into the device tree
+        let tree = MockTree::new();
+        let device = energy(&tree, "devices/BAT0", &[]);
+        let class = tree.path().join("power_supply");
+        fs::create_dir(&class).unwrap();
+        symlink(&device, class.join("BAT0")).unwrap();
+        // A second link to the same device is only listed once
+        symlink(&device, class.join("BAT1")).unwrap();
+        symlink(class.join("gone"), class.join("BAT2")).unwrap();
+
+        let (batteries, warnings) = find_batteries(&[class], false, false);
+        assert_eq!(batteries.len(), 1);
+        assert_eq!(
+            fs::canonicalize(&batteries[0]).unwrap(),
+            fs::canonicalize(&device).unwrap()
+        );
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].message.contains("BAT2"));
+    }
+
+    #[test]
+    fn peripheral_scope() {
+        let tree = MockTree::new();
+        let mouse = tree.battery("hidpp_battery_0", &[("scope", "Device")]);
+        let system = tree.battery("BAT0", &[("scope", "System")]);
+        let unscoped = tree.battery("BAT1", &[]);
+        assert!(is_peripheral(&mouse));
+        assert!(!is_peripheral(&system));
+        assert!(!is_peripheral(&unscoped));
+    }
+
+    #[test]
+    fn discovery_filters_peripherals() {
+        let tree = MockTree::new();
+        let bat0 = energy(&tree, "BAT0", &[("scope", "System")]);
+        let mouse = tree.battery(
+            "hidpp_battery_0",
+            &[("scope", "Device"), ("capacity", "70")],
+        );
+        let roots = [tree.path().to_path_buf()];
+
+        let (batteries, _) = find_batteries(&roots, false, false);
+        assert_eq!(batteries, vec![bat0.clone()]);
+
+        let (mut batteries, _) = find_batteries(&roots, false, true);
+        batteries.sort();
+        assert_eq!(batteries, vec![bat0, mouse]);
+    }
+
+    #[test]
+    fn invalid_utf8_is_decoded_lossily() {
+        let tree = MockTree::new();
+        let path = energy(&tree, "BAT0", &[]);
+        tree.write(&path, "serial_number", b"SN\xff42\n");
+        let mut warnings = Vec::new();
+        let serial =
+            read_str_battery_attribute(&path, BatteryAttribute::SerialNumber, &mut warnings)
+                .unwrap();
+        assert_eq!(serial.trim(), "SN\u{fffd}42");
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].message.contains("invalid UTF-8"));
+    }
+
+    #[test]
+    fn unrecognized_status_is_unknown() {
+        let tree = MockTree::new();
+        let path = energy(&tree, "BAT0", &[("status", "Not charging")]);
+        let (battery, _) = Battery::new(&path).unwrap();
+        assert_eq!(battery.status, BatteryStatus::NotCharging);
+
+        tree.write(&path, "status", "Bogus\n");
+        let (battery, warnings) = Battery::new(&path).unwrap();
+        assert_eq!(battery.status, BatteryStatus::Unknown);
+        assert!(warnings
+            .iter()
+            .any(|warning| warning.message.contains("invalid value 'Bogus'")));
+    }
 }
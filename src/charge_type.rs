@@ -0,0 +1,66 @@
+use crate::{lock::ThresholdLock, thresholds};
+use std::{fs, io, path::Path};
+
+const CHARGE_TYPE_FILE: &str = "charge_type";
+// Newer kernels list the accepted values here, e.g. "Fast [Standard] Long_Life"
+const CHARGE_TYPES_FILE: &str = "charge_types";
+
+// Values the kernel's power_supply class defines for charge_type
+const KNOWN_TYPES: &[&str] = &[
+    "Trickle",
+    "Fast",
+    "Standard",
+    "Adaptive",
+    "Custom",
+    "Long Life",
+    "Bypass",
+];
+
+pub fn is_supported(bat_path: &Path) -> bool {
+    bat_path.join(CHARGE_TYPE_FILE).exists()
+}
+
+pub fn read(bat_path: &Path) -> io::Result<String> {
+    let raw = fs::read_to_string(bat_path.join(CHARGE_TYPE_FILE))?;
+    Ok(raw.trim().to_string())
+}
+
+// The values this driver advertises, or the kernel's full list when it
+// doesn't say
+pub fn available(bat_path: &Path) -> Vec<String> {
+    match fs::read_to_string(bat_path.join(CHARGE_TYPES_FILE)) {
+        Ok(raw) => raw
+            .split_whitespace()
+            .map(|value| value.trim_matches(['[', ']']).to_string())
+            .collect(),
+        Err(_) => KNOWN_TYPES.iter().map(|value| value.to_string()).collect(),
+    }
+}
+
+// Matches case-insensitively and writes the driver's own spelling
+pub fn write(bat_path: &Path, value: &str) -> Result<(), String> {
+    if !is_supported(bat_path) {
+        return Err(format!(
+            "{} has no charge_type attribute",
+            bat_path.display()
+        ));
+    }
+
+    let available = available(bat_path);
+    let normalized = |s: &str| s.replace('_', " ").to_lowercase();
+    let Some(value) = available
+        .iter()
+        .find(|candidate| normalized(candidate) == normalized(value))
+    else {
+        return Err(format!(
+            "invalid charge type '{}', expected one of: {}",
+            value,
+            available.join(", ")
+        ));
+    };
+
+    let _lock =
+        ThresholdLock::acquire().map_err(|e| format!("failed to lock thresholds: {}", e))?;
+    thresholds::write_attribute(&bat_path.join(CHARGE_TYPE_FILE), value)
+        .map_err(|e| format!("failed to write {}", e))
+}
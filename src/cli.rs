@@ -1,5 +1,14 @@
-use clap::Parser;
-use std::path::PathBuf;
+use crate::{
+    battery::{self, Rounding},
+    theme::ThemeName,
+    thresholds::{ParseThresholdKindError, ThresholdKind},
+};
+use clap::{
+    builder::{PossibleValuesParser, TypedValueParser},
+    CommandFactory, Parser, Subcommand, ValueEnum,
+};
+use clap_complete::{ArgValueCandidates, CompletionCandidate};
+use std::{path::PathBuf, str::FromStr, time::Duration};
 
 #[derive(Debug, Parser)]
 #[command(
@@ -7,20 +16,516 @@ use std::path::PathBuf;
     about = "Set or read battery charge threshold on ASUS laptops"
 )]
 pub struct Cli {
-    #[arg(short, long)]
-    pub path: Option<PathBuf>,
+    #[arg(
+        short,
+        long,
+        value_delimiter = ':',
+        help = "Power supply directory to scan; repeat it or separate several with ':'"
+    )]
+    pub path: Vec<PathBuf>,
+
+    #[arg(
+        short,
+        long,
+        value_name = "NAME",
+        add = ArgValueCandidates::new(battery_candidates),
+        help = "Battery to operate on (e.g. BAT1) instead of the first one found"
+    )]
+    pub battery: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Ignore battery bays that report no battery present"
+    )]
+    pub skip_empty: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Also monitor UPS devices (power_supply entries of type UPS)"
+    )]
+    pub include_ups: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Also list Device-scoped batteries such as wireless mice and keyboards"
+    )]
+    pub include_peripherals: bool,
 
     #[arg(short, long)]
     pub value: Option<u8>,
 
+    // The possible values keep --help and completions listing them; parsing
+    // itself goes through KindArg::from_str
     #[arg(
         short = 'k',
         long,
+        ignore_case = true,
         default_value = "end",
-        help = "Which threshold kind to set (start or end)"
+        value_parser = PossibleValuesParser::new(["start", "end", "both"])
+            .try_map(|kind| kind.parse::<KindArg>()),
+        help = "Which threshold kind to set (start, end, or both with --margin)"
+    )]
+    pub kind: KindArg,
+
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        requires = "value",
+        help = "With --kind both, set the start threshold this far below the end (--value)"
+    )]
+    pub margin: Option<u8>,
+
+    #[arg(
+        long,
+        conflicts_with = "value",
+        help = "Set the start threshold and print the resulting thresholds"
+    )]
+    pub set_start: Option<u8>,
+
+    #[arg(
+        long,
+        conflicts_with = "value",
+        help = "Set the end threshold and print the resulting thresholds"
+    )]
+    pub set_end: Option<u8>,
+
+    #[arg(
+        long,
+        value_name = "WH",
+        conflicts_with_all = ["value", "set_end", "tui"],
+        help = "Set the end threshold as an absolute energy in Wh, converted to a percentage of energy_full"
+    )]
+    pub end_wh: Option<f32>,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["value", "set_start", "set_end", "tui", "end_wh"],
+        help = "Apply the longevity preset (40-80%, or [longevity] in the config); lithium-ion cells wear fastest when held near full charge"
+    )]
+    pub longevity: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["value", "set_start", "set_end", "tui", "end_wh", "longevity"],
+        help = "Charge to full capacity (0-100%), e.g. before a long trip, at the cost of faster wear"
+    )]
+    pub maximize_capacity: bool,
+
+    #[arg(
+        short,
+        long,
+        help = "Suppress confirmation output after setting thresholds"
+    )]
+    pub quiet: bool,
+
+    #[arg(
+        long,
+        help = "Show each threshold's old and new value (\"end: 60% → 80%\") and skip the write when nothing changes"
+    )]
+    pub diff: bool,
+
+    #[arg(
+        long,
+        help = "Validate and show the threshold changes (like --diff) without writing them"
+    )]
+    pub dry_run: bool,
+
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        help = "Reject end thresholds below this value (default 20, or min_end in the config)"
+    )]
+    pub min_end: Option<u8>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Only display battery information; refuse every threshold change"
+    )]
+    pub read_only: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Never write the start threshold; only manage the end (same as manage_start = false)"
+    )]
+    pub end_only: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Apply thresholds even when they fail the --min-end safety check or the discharge guard blocks them"
+    )]
+    pub force: bool,
+
+    #[arg(long, help = "Print the battery status repeatedly until interrupted")]
+    pub watch: bool,
+
+    #[arg(
+        long,
+        default_value = "5s",
+        value_parser = parse_interval,
+        help = "How often to refresh in watch mode and with --bar --follow (e.g. 30s, 5m, 1h)"
+    )]
+    pub interval: Duration,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        requires = "watch",
+        help = "Append a CSV row per watch interval to FILE"
+    )]
+    pub csv: Option<PathBuf>,
+
+    #[arg(
+        long,
+        requires_all = ["watch", "json"],
+        help = "With --watch --json, print a sample every interval instead of only changes"
+    )]
+    pub all_samples: bool,
+
+    #[arg(
+        long,
+        requires = "watch",
+        help = "With --watch, re-apply the config's [thresholds] whenever AC is connected"
+    )]
+    pub reapply_on_ac: bool,
+
+    #[arg(
+        long,
+        requires = "watch",
+        conflicts_with = "battery",
+        help = "With --watch, follow every battery instead of just the first"
+    )]
+    pub all: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["watch", "tui", "value", "set_start", "set_end"],
+        help = "Print only the charge percentage of the battery as an integer"
+    )]
+    pub percent: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["watch", "tui", "value", "set_start", "set_end", "percent", "json"],
+        help = "Exit 0 if the battery's thresholds can be written, 1 otherwise"
     )]
-    pub kind: String,
+    pub check_support: bool,
+
+    #[arg(
+        long,
+        requires = "check_support",
+        help = "With --check-support, print the reason as well"
+    )]
+    pub verbose: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["watch", "tui", "value", "set_start", "set_end", "percent", "json", "check_support"],
+        help = "Print a monitoring-plugin status line for the battery's health and charge and exit 0/1/2 (OK/WARNING/CRITICAL)"
+    )]
+    pub nagios: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["watch", "tui", "value", "set_start", "set_end", "percent", "json"],
+        help = "Print a compact table of the battery's charge, status, power, cycles, health, temperature and thresholds"
+    )]
+    pub stats: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["watch", "tui", "value", "set_start", "set_end", "percent", "stats", "json"],
+        help = "Print the charge as a single-line bar"
+    )]
+    pub bar: bool,
+
+    #[arg(
+        long,
+        requires = "bar",
+        help = "Redraw the bar in place every --interval until interrupted"
+    )]
+    pub follow: bool,
+
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        value_parser = clap::value_parser!(u8).range(0..=100),
+        help = "Block until the charge reaches PERCENT, polling every --interval"
+    )]
+    pub wait_until: Option<u8>,
+
+    #[arg(
+        long,
+        requires = "wait_until",
+        help = "With --wait-until, wait for the charge to drop to PERCENT instead"
+    )]
+    pub below: bool,
+
+    #[arg(
+        long,
+        value_parser = parse_duration,
+        requires = "wait_until",
+        help = "Give up on --wait-until after this long (e.g. 30m, 2h) and exit with status 2"
+    )]
+    pub timeout: Option<Duration>,
 
     #[arg(long, help = "Launch the interactive terminal UI")]
     pub tui: bool,
+
+    #[arg(
+        long,
+        requires = "tui",
+        help = "Start the TUI in the side-by-side view of all batteries"
+    )]
+    pub compare: bool,
+
+    #[arg(
+        long,
+        help = "Disable the TUI pulse shown when a battery finishes charging"
+    )]
+    pub no_animations: bool,
+
+    #[arg(
+        long,
+        help = "Start the TUI with the warnings/status footer hidden (toggle it with f)"
+    )]
+    pub no_footer: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "How the TUI displays the charge percentage (default exact, or round in the config)"
+    )]
+    pub round: Option<Rounding>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "TUI color theme (default dark, or theme in the config)"
+    )]
+    pub theme: Option<ThemeName>,
+
+    #[arg(
+        long,
+        help = "Record a daily cycle count/health snapshot and enable the TUI history view"
+    )]
+    pub history: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Disable colored output (also honors the NO_COLOR environment variable)"
+    )]
+    pub no_color: bool,
+
+    #[arg(long, global = true, help = "Print machine-readable JSON output")]
+    pub json: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+// "both" sets end to --value and start --margin below it in one operation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KindArg {
+    Single(ThresholdKind),
+    Both,
+}
+
+impl KindArg {
+    pub fn single(self) -> Option<ThresholdKind> {
+        match self {
+            KindArg::Single(kind) => Some(kind),
+            KindArg::Both => None,
+        }
+    }
+}
+
+// Everything but "both" is left to ThresholdKind's parser
+impl FromStr for KindArg {
+    type Err = ParseThresholdKindError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().eq_ignore_ascii_case("both") {
+            return Ok(KindArg::Both);
+        }
+        s.parse().map(KindArg::Single)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum SwitchArg {
+    On,
+    Off,
+    Toggle,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Print the version and the battery features detected on this machine
+    About,
+
+    /// Diagnose battery and charge threshold support on this machine
+    Doctor,
+
+    /// Write a diagnostic bundle (sysfs attributes, threshold scheme, system info) for bug reports
+    Export {
+        #[arg(
+            short,
+            long,
+            default_value = "batty-export.json",
+            help = "File to write the bundle to, or - for stdout"
+        )]
+        output: PathBuf,
+    },
+
+    /// Interactively pick a battery and thresholds, save them to the config and optionally install the boot service
+    Setup,
+
+    /// Apply the thresholds saved in the config ([thresholds] start/end)
+    Apply,
+
+    /// Install a systemd service that runs `batty apply` at boot
+    InstallService,
+
+    /// Stop, disable and remove the systemd service installed by install-service
+    UninstallService,
+
+    /// List all detected batteries with their charge, status and thresholds
+    List,
+
+    /// Serve battery status as JSON (/status) and Prometheus metrics (/metrics)
+    #[cfg(feature = "serve")]
+    Serve {
+        #[arg(long, default_value_t = 8080, help = "Port to listen on")]
+        port: u16,
+    },
+
+    /// Show or set the low-capacity alarm level (as a percentage of full capacity)
+    Alarm {
+        #[arg(
+            value_parser = clap::value_parser!(u8).range(0..=100),
+            help = "New alarm level in percent; omit to print the current one"
+        )]
+        value: Option<u8>,
+    },
+
+    /// Show or set the charging mode (charge_type), e.g. Standard, Fast or Trickle
+    ChargeType {
+        #[arg(help = "New charge type; omit to print the current one and the accepted values")]
+        value: Option<String>,
+    },
+
+    /// Show or switch fast charging, where the firmware offers it (faster, but wears the battery)
+    FastCharge {
+        #[arg(help = "New state; omit to print the current one")]
+        state: Option<SwitchArg>,
+    },
+
+    /// Set a threshold now and revert it to its previous value after a duration
+    SetUntil {
+        #[arg(help = "Threshold value to apply until the duration elapses")]
+        value: u8,
+
+        #[arg(value_parser = parse_duration, help = "How long to keep the value (e.g. 90s, 30m, 8h, 1d)")]
+        duration: Duration,
+
+        #[arg(
+            long,
+            help = "Wait in the foreground and revert when done instead of scheduling a systemd timer"
+        )]
+        foreground: bool,
+    },
+}
+
+impl Cli {
+    pub fn requests_write(&self) -> bool {
+        let sets_thresholds = self.value.is_some()
+            || self.set_start.is_some()
+            || self.set_end.is_some()
+            || self.end_wh.is_some()
+            || self.longevity
+            || self.maximize_capacity;
+        (sets_thresholds && !self.dry_run)
+            || self.reapply_on_ac
+            || matches!(
+                self.command,
+                Some(Command::SetUntil { .. })
+                    | Some(Command::Setup)
+                    | Some(Command::Apply)
+                    | Some(Command::InstallService)
+                    | Some(Command::Alarm { value: Some(_) })
+                    | Some(Command::ChargeType { value: Some(_) })
+                    | Some(Command::FastCharge { state: Some(_) })
+            )
+    }
+}
+
+pub fn command() -> clap::Command {
+    Cli::command()
+}
+
+// Runs while the shell is completing, so --path on the command line is not known yet
+fn battery_candidates() -> Vec<CompletionCandidate> {
+    let power_supply_paths = battery::power_supply_paths(Vec::new());
+    let mut names: Vec<_> = battery::find_batteries(&power_supply_paths, false, false)
+        .0
+        .iter()
+        .filter_map(|path| path.file_name())
+        .map(|name| name.to_os_string())
+        .collect();
+    names.sort();
+    names.into_iter().map(CompletionCandidate::new).collect()
+}
+
+// Anything shorter would effectively busy-loop on sysfs reads
+const MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+fn parse_duration(input: &str) -> Result<Duration, String> {
+    let duration = humantime::parse_duration(input.trim()).map_err(|e| e.to_string())?;
+    if duration.is_zero() {
+        return Err("duration must be greater than zero".to_string());
+    }
+    Ok(duration)
+}
+
+fn parse_interval(input: &str) -> Result<Duration, String> {
+    let interval = parse_duration(input)?;
+    if interval < MIN_INTERVAL {
+        return Err(format!(
+            "interval must be at least {}",
+            humantime::format_duration(MIN_INTERVAL)
+        ));
+    }
+    Ok(interval)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kind_parses_through_threshold_kind() {
+        let kind = |args: &[&str]| {
+            Cli::try_parse_from(["batty"].iter().chain(args))
+                .map(|cli| cli.kind)
+                .map_err(|e| e.kind())
+        };
+        assert_eq!(kind(&[]), Ok(KindArg::Single(ThresholdKind::End)));
+        assert_eq!(
+            kind(&["--kind", "Start"]),
+            Ok(KindArg::Single(ThresholdKind::Start))
+        );
+        assert_eq!(kind(&["-k", "BOTH"]), Ok(KindArg::Both));
+        assert_eq!(
+            kind(&["--kind", "stop"]),
+            Err(clap::error::ErrorKind::InvalidValue)
+        );
+        assert!("stop".parse::<KindArg>().is_err());
+    }
 }
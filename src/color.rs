@@ -0,0 +1,52 @@
+use std::{
+    env,
+    io::{self, IsTerminal},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Dim,
+}
+
+impl Color {
+    fn code(self) -> &'static str {
+        match self {
+            Self::Red => "31",
+            Self::Green => "32",
+            Self::Yellow => "33",
+            Self::Dim => "2",
+        }
+    }
+
+    pub fn for_percentage(percentage: f32) -> Self {
+        if percentage < 20.0 {
+            Self::Red
+        } else if percentage < 50.0 {
+            Self::Yellow
+        } else {
+            Self::Green
+        }
+    }
+}
+
+// Every non-TUI output path goes through paint(), so this is the single
+// place deciding whether ANSI escapes are emitted (https://no-color.org)
+pub fn init(no_color_flag: bool) {
+    let no_color_env = env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    let enabled = !no_color_flag && !no_color_env && io::stdout().is_terminal();
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn paint(text: &str, color: Color) -> String {
+    if ENABLED.load(Ordering::Relaxed) {
+        format!("\x1b[{}m{}\x1b[0m", color.code(), text)
+    } else {
+        text.to_string()
+    }
+}
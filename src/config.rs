@@ -0,0 +1,234 @@
+use crate::{
+    battery::{EnergyScale, FullDisplay, Rounding, WearCutoffs},
+    keymap::KeyList,
+    nagios::NagiosLimits,
+    theme::ThemeName,
+    thresholds::{self, DischargeGuard},
+    watch::CriticalAction,
+};
+use serde::{Deserialize, Deserializer};
+use std::{collections::HashMap, env, fs, path::PathBuf, time::Duration};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub min_end: Option<u8>,
+    pub round: Option<Rounding>,
+    pub full_display: FullDisplay,
+    pub theme: Option<ThemeName>,
+    pub longevity: Option<Preset>,
+    // Battery used by CLI operations when --battery isn't given
+    pub battery: Option<String>,
+    // Thresholds applied by `batty apply` (and the boot service)
+    pub thresholds: Option<Preset>,
+    // ACPI platform profile written whenever thresholds are applied
+    pub platform_profile: Option<String>,
+    // Extra named presets, bound to the TUI's digit keys after the built-ins
+    pub profiles: Vec<Profile>,
+    pub keys: HashMap<String, KeyList>,
+    pub read_only: bool,
+    // false leaves charge_control_start_threshold untouched
+    pub manage_start: Option<bool>,
+    // What to do when thresholds are written while running on battery
+    pub discharge_guard: DischargeGuard,
+    pub energy_scale: EnergyScale,
+    pub power_smoothing: Option<f32>,
+    // How many power readings the TUI averages for the displayed watts
+    pub power_samples: Option<usize>,
+    // Health (percent of design capacity) below which the TUI warns; 0 disables
+    pub health_warning: Option<u8>,
+    // Cycle and health cutoffs for the Good/Fair/Worn wear estimate
+    pub wear: WearCutoffs,
+    // Warning and critical levels for --nagios
+    pub nagios: NagiosLimits,
+    // How often the TUI re-reads the battery, e.g. "5s"
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub refresh_interval: Option<Duration>,
+    // Present to make the TUI poll faster while charging and slower at rest
+    pub adaptive_refresh: Option<AdaptiveRefresh>,
+    // Shell command run by --watch when the charge drops to critical_percent
+    pub critical_percent: Option<u8>,
+    pub critical_command: Option<String>,
+}
+
+// Weight of the newest power reading in the TUI's moving average
+pub const DEFAULT_POWER_SMOOTHING: f32 = 0.2;
+// One second of readings at the default refresh interval
+pub const DEFAULT_POWER_SAMPLES: usize = 4;
+// Matches the TUI's frame rate, so every frame shows fresh readings
+pub const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_millis(250);
+// Adaptive refresh: while charging or close to a threshold, and when idle
+pub const DEFAULT_FAST_REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+pub const DEFAULT_IDLE_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+// Around 80% of design capacity is where most vendors consider a battery worn
+pub const DEFAULT_HEALTH_WARNING: u8 = 80;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Preset {
+    pub start: u8,
+    pub end: u8,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdaptiveRefresh {
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub fast: Option<Duration>,
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub idle: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    pub name: String,
+    pub start: u8,
+    pub end: u8,
+}
+
+impl Config {
+    // A missing config file is not an error; every key has a built-in default
+    pub fn load() -> Result<Self, String> {
+        let path = config_path();
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+
+        let config: Self = toml::from_str(&contents)
+            .map_err(|e| format!("Invalid config {}: {}", path.display(), e))?;
+
+        if let Some(alpha) = config.power_smoothing {
+            if !(alpha > 0.0 && alpha <= 1.0) {
+                return Err(format!(
+                    "Invalid config {}: power_smoothing must be greater than 0 and at most 1",
+                    path.display()
+                ));
+            }
+        }
+
+        if config.power_samples == Some(0) {
+            return Err(format!(
+                "Invalid config {}: power_samples must be at least 1",
+                path.display()
+            ));
+        }
+
+        for profile in &config.profiles {
+            if profile.end > 100 || profile.start >= profile.end {
+                return Err(format!(
+                    "Invalid config {}: profile {:?} needs start < end <= 100",
+                    path.display(),
+                    profile.name
+                ));
+            }
+        }
+
+        let wear = &config.wear;
+        if wear.fair_health > 100
+            || wear.worn_health > wear.fair_health
+            || wear.worn_cycles < wear.fair_cycles
+        {
+            return Err(format!(
+                "Invalid config {}: [wear] needs worn_health <= fair_health <= 100 and fair_cycles <= worn_cycles",
+                path.display()
+            ));
+        }
+
+        let nagios = &config.nagios;
+        if nagios.health_warning > 100
+            || nagios.health_critical > nagios.health_warning
+            || nagios.charge_warning > 100
+            || nagios.charge_critical > nagios.charge_warning
+        {
+            return Err(format!(
+                "Invalid config {}: [nagios] needs critical <= warning <= 100 for health and charge",
+                path.display()
+            ));
+        }
+
+        if config.health_warning.is_some_and(|percent| percent > 100) {
+            return Err(format!(
+                "Invalid config {}: health_warning must be between 0 and 100",
+                path.display()
+            ));
+        }
+
+        match (config.critical_percent, &config.critical_command) {
+            (Some(percent), _) if percent > 100 => {
+                return Err(format!(
+                    "Invalid config {}: critical_percent must be between 0 and 100",
+                    path.display()
+                ));
+            }
+            (Some(_), None) | (None, Some(_)) => {
+                return Err(format!(
+                    "Invalid config {}: critical_percent and critical_command must be set together",
+                    path.display()
+                ));
+            }
+            _ => {}
+        }
+
+        Ok(config)
+    }
+
+    // The built-in presets first, then the user's profiles in file order
+    pub fn all_profiles(&self) -> Vec<Profile> {
+        let (start, end) = self
+            .longevity
+            .map(|p| (p.start, p.end))
+            .unwrap_or(thresholds::LONGEVITY_PRESET);
+        let (max_start, max_end) = thresholds::MAXIMIZE_CAPACITY_PRESET;
+        let mut profiles = vec![
+            Profile {
+                name: "longevity".to_string(),
+                start,
+                end,
+            },
+            Profile {
+                name: "maximize-capacity".to_string(),
+                start: max_start,
+                end: max_end,
+            },
+        ];
+        profiles.extend(self.profiles.iter().cloned());
+        profiles
+    }
+
+    pub fn critical_action(&self) -> Option<CriticalAction> {
+        Some(CriticalAction {
+            percent: self.critical_percent?,
+            command: self.critical_command.clone()?,
+        })
+    }
+}
+
+fn deserialize_duration<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Duration>, D::Error> {
+    let raw = String::deserialize(deserializer)?;
+    humantime::parse_duration(&raw)
+        .map(Some)
+        .map_err(|e| serde::de::Error::custom(format!("invalid duration {:?}: {}", raw, e)))
+}
+
+// BATTY_CONFIG, then the user's XDG config, then the system-wide file
+pub fn config_path() -> PathBuf {
+    if let Some(path) = env::var_os("BATTY_CONFIG").filter(|p| !p.is_empty()) {
+        return PathBuf::from(path);
+    }
+
+    let user = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("batty/config.toml"));
+
+    match user {
+        Some(path) if path.exists() => path,
+        _ => PathBuf::from("/etc/batty/config.toml"),
+    }
+}
@@ -0,0 +1,233 @@
+use crate::{
+    battery::{self, Battery, Wear, WearCutoffs},
+    color::{self, Color},
+    thresholds::{self, ThresholdKind},
+};
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+// Kernel modules known to expose charge_control_* thresholds
+const THRESHOLD_MODULES: &[&str] = &[
+    "asus_wmi",
+    "asus_nb_wmi",
+    "thinkpad_acpi",
+    "huawei_wmi",
+    "ideapad_laptop",
+    "dell_laptop",
+    "lg_laptop",
+    "msi_ec",
+    "system76_acpi",
+    "samsung_laptop",
+];
+
+enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pass => write!(f, "{}", color::paint("[PASS]", Color::Green)),
+            Self::Warn => write!(f, "{}", color::paint("[WARN]", Color::Yellow)),
+            Self::Fail => write!(f, "{}", color::paint("[FAIL]", Color::Red)),
+        }
+    }
+}
+
+struct Check {
+    status: CheckStatus,
+    message: String,
+    suggestion: Option<String>,
+}
+
+impl Check {
+    fn pass(message: impl Into<String>) -> Self {
+        Self {
+            status: CheckStatus::Pass,
+            message: message.into(),
+            suggestion: None,
+        }
+    }
+
+    fn warn(message: impl Into<String>, suggestion: impl Into<String>) -> Self {
+        Self {
+            status: CheckStatus::Warn,
+            message: message.into(),
+            suggestion: Some(suggestion.into()),
+        }
+    }
+
+    fn fail(message: impl Into<String>, suggestion: impl Into<String>) -> Self {
+        Self {
+            status: CheckStatus::Fail,
+            message: message.into(),
+            suggestion: Some(suggestion.into()),
+        }
+    }
+}
+
+// Prints the checklist and returns false when any check failed
+pub fn run(power_supply_paths: &[PathBuf], bat_paths: &[PathBuf], wear: &WearCutoffs) -> bool {
+    let power_supply_path = battery::display_paths(power_supply_paths);
+    let mut checks = Vec::new();
+
+    if bat_paths.is_empty() {
+        checks.push(Check::fail(
+            format!("No batteries found in {}", power_supply_path),
+            "Make sure you're running on a laptop with battery support, or pass --path.",
+        ));
+    } else {
+        checks.push(Check::pass(format!(
+            "Found {} battery(ies) in {}",
+            bat_paths.len(),
+            power_supply_path
+        )));
+    }
+
+    for path in bat_paths {
+        check_battery(path, &mut checks);
+        check_wear(path, wear, &mut checks);
+    }
+
+    checks.push(check_modules());
+
+    let mut ok = true;
+    for check in &checks {
+        println!("{} {}", check.status, check.message);
+        if let Some(suggestion) = &check.suggestion {
+            println!(
+                "       {}",
+                color::paint(&format!("→ {}", suggestion), Color::Dim)
+            );
+        }
+        if matches!(check.status, CheckStatus::Fail) {
+            ok = false;
+        }
+    }
+
+    ok
+}
+
+fn check_battery(path: &Path, checks: &mut Vec<Check>) {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("unknown");
+
+    match fs::read_to_string(path.join("type")) {
+        Ok(kind) if kind.trim().eq_ignore_ascii_case("battery") => {
+            checks.push(Check::pass(format!("{}: type is Battery", name)))
+        }
+        Ok(kind) => checks.push(Check::warn(
+            format!("{}: type is {}", name, kind.trim()),
+            "This power supply may not be a laptop battery.",
+        )),
+        Err(_) => checks.push(Check::warn(
+            format!("{}: no type file", name),
+            "The driver does not report a power supply type.",
+        )),
+    }
+
+    let has = |file: &str| path.join(file).exists();
+    if has("energy_now") && has("energy_full") {
+        checks.push(Check::pass(format!(
+            "{}: energy_now/energy_full available",
+            name
+        )));
+    } else if has("charge_now") && has("charge_full") {
+        checks.push(Check::pass(format!(
+            "{}: charge_now/charge_full available",
+            name
+        )));
+    } else if has("capacity") {
+        checks.push(Check::warn(
+            format!("{}: only capacity is available", name),
+            "Charge percentage works, but power and health cannot be computed.",
+        ));
+    } else {
+        checks.push(Check::fail(
+            format!("{}: no energy, charge or capacity files", name),
+            "The battery driver does not expose charge information.",
+        ));
+    }
+
+    let end_path = thresholds::get_path_for_kind(path, &ThresholdKind::End);
+    let start_path = thresholds::get_path_for_kind(path, &ThresholdKind::Start);
+    if !end_path.exists() {
+        checks.push(Check::fail(
+            format!("{}: no charge threshold files", name),
+            "Load your vendor's platform driver (e.g. asus_wmi, thinkpad_acpi) or check kernel support.",
+        ));
+        return;
+    }
+
+    checks.push(Check::pass(format!(
+        "{}: {} present",
+        name,
+        end_path.display()
+    )));
+    if !start_path.exists() {
+        checks.push(Check::warn(
+            format!("{}: no start threshold file", name),
+            "Only the end threshold can be controlled on this hardware.",
+        ));
+    }
+
+    match thresholds::check_writable(path) {
+        Ok(()) => checks.push(Check::pass(format!("{}: thresholds are writable", name))),
+        Err(e) => checks.push(Check::warn(
+            format!("{}: thresholds are not writable ({})", name, e),
+            "Run batty with sudo or add a udev rule granting write access.",
+        )),
+    }
+}
+
+fn check_wear(path: &Path, cutoffs: &WearCutoffs, checks: &mut Vec<Check>) {
+    let Ok((battery, _)) = Battery::new(path) else {
+        return;
+    };
+    if !battery.present {
+        return;
+    }
+    let name = battery.name();
+    let wear = battery.wear_estimate(cutoffs);
+    let message = match (battery.cycles, battery.health()) {
+        (Some(cycles), Some(health)) => format!(
+            "{}: wear {} ({} cycles, {:.0}% health)",
+            name,
+            wear.label(),
+            cycles,
+            health
+        ),
+        _ => format!("{}: wear {}", name, wear.label()),
+    };
+    checks.push(match (wear, wear.advice()) {
+        (Wear::Unknown, _) => Check::warn(
+            message,
+            "The driver doesn't report both the cycle count and the design capacity.",
+        ),
+        (_, Some(advice)) => Check::warn(message, advice),
+        (_, None) => Check::pass(message),
+    });
+}
+
+fn check_modules() -> Check {
+    let loaded: Vec<&str> = THRESHOLD_MODULES
+        .iter()
+        .copied()
+        .filter(|module| Path::new("/sys/module").join(module).exists())
+        .collect();
+
+    if loaded.is_empty() {
+        Check::warn(
+            "No known threshold driver module is loaded",
+            "Install or load your laptop's platform driver (e.g. `modprobe asus_wmi`).",
+        )
+    } else {
+        Check::pass(format!("Loaded driver modules: {}", loaded.join(", ")))
+    }
+}
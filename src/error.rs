@@ -0,0 +1,92 @@
+use std::{
+    fmt, io,
+    path::{Path, PathBuf},
+};
+
+// Failures reading or writing power_supply attributes, so callers can tell a
+// missing file from a garbled one without matching on message text
+#[derive(Debug)]
+pub enum BattyError {
+    // The driver doesn't expose this attribute
+    AttributeMissing(PathBuf),
+    // The file was read but doesn't hold a usable value
+    AttributeUnparseable { path: PathBuf, value: String },
+    PermissionDenied { path: PathBuf, source: io::Error },
+    // The directory is a power supply of another type (e.g. an AC adapter)
+    NotABattery(PathBuf),
+    // Taking the lock that serializes threshold writes failed
+    Lock(io::Error),
+    // BATTY_WRITE_HELPER could not be started or exited unsuccessfully
+    WriteHelper { helper: PathBuf, reason: String },
+    // Any other I/O failure on the attribute
+    Io { path: PathBuf, source: io::Error },
+}
+
+impl BattyError {
+    // Classifies a failed read or write of `path` by its I/O error kind
+    pub fn from_io(path: &Path, source: io::Error) -> Self {
+        let path = path.to_path_buf();
+        match source.kind() {
+            io::ErrorKind::NotFound => BattyError::AttributeMissing(path),
+            io::ErrorKind::PermissionDenied => BattyError::PermissionDenied { path, source },
+            _ => BattyError::Io { path, source },
+        }
+    }
+
+    pub fn unparseable(path: &Path, value: &str) -> Self {
+        BattyError::AttributeUnparseable {
+            path: path.to_path_buf(),
+            value: value.to_string(),
+        }
+    }
+
+    pub fn is_missing(&self) -> bool {
+        matches!(self, BattyError::AttributeMissing(_))
+    }
+}
+
+impl fmt::Display for BattyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BattyError::AttributeMissing(path) => write!(f, "{} does not exist", path.display()),
+            BattyError::AttributeUnparseable { path, value } => {
+                write!(f, "{} holds an invalid value '{}'", path.display(), value)
+            }
+            BattyError::PermissionDenied { path, source } | BattyError::Io { path, source } => {
+                write!(f, "{}: {}", path.display(), source)
+            }
+            BattyError::NotABattery(path) => write!(f, "{} is not a battery", path.display()),
+            BattyError::Lock(source) => write!(f, "failed to lock thresholds: {}", source),
+            BattyError::WriteHelper { helper, reason } => {
+                write!(f, "write helper {} {}", helper.display(), reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BattyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BattyError::PermissionDenied { source, .. }
+            | BattyError::Io { source, .. }
+            | BattyError::Lock(source) => Some(source),
+            _ => None,
+        }
+    }
+}
+
+// For callers that still report plain I/O errors (the TUI, history, alarm)
+impl From<BattyError> for io::Error {
+    fn from(e: BattyError) -> Self {
+        let kind = match &e {
+            BattyError::AttributeMissing(_) => io::ErrorKind::NotFound,
+            BattyError::AttributeUnparseable { .. } => io::ErrorKind::InvalidData,
+            BattyError::PermissionDenied { .. } | BattyError::WriteHelper { .. } => {
+                io::ErrorKind::PermissionDenied
+            }
+            BattyError::NotABattery(_) => io::ErrorKind::InvalidInput,
+            BattyError::Lock(source) | BattyError::Io { source, .. } => source.kind(),
+        };
+        io::Error::new(kind, e)
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{about, ac, alarm, battery};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const EXPORT_VERSION: u32 = 1;
+
+// Everything needed to reproduce a bug report, in one file
+#[derive(Serialize)]
+struct Bundle {
+    export_version: u32,
+    batty_version: &'static str,
+    generated_at: u64,
+    system: SystemInfo,
+    power_supply_path: String,
+    batteries: Vec<BatteryDump>,
+    ac_adapters: Vec<AdapterDump>,
+}
+
+#[derive(Serialize)]
+struct SystemInfo {
+    os: Option<String>,
+    kernel: Option<String>,
+    arch: &'static str,
+}
+
+#[derive(Serialize)]
+struct BatteryDump {
+    name: String,
+    present: bool,
+    serial_number: Option<String>,
+    charge_source: &'static str,
+    threshold_scheme: String,
+    alarm_supported: bool,
+    // Unreadable files are kept with the error instead of failing the export
+    attributes: BTreeMap<String, String>,
+    warnings: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct AdapterDump {
+    name: String,
+    online: bool,
+    power_watts: Option<f32>,
+}
+
+pub fn run(
+    power_supply_paths: &[PathBuf],
+    bat_paths: &[PathBuf],
+    output: &Path,
+) -> Result<(), String> {
+    let bundle = Bundle {
+        export_version: EXPORT_VERSION,
+        batty_version: env!("CARGO_PKG_VERSION"),
+        generated_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        system: SystemInfo {
+            os: os_name(),
+            kernel: fs::read_to_string("/proc/sys/kernel/osrelease")
+                .ok()
+                .map(|s| s.trim().to_string()),
+            arch: std::env::consts::ARCH,
+        },
+        // Several roots are joined with ':', as in BATTY_POWER_SUPPLY_PATH
+        power_supply_path: battery::display_paths(power_supply_paths),
+        batteries: bat_paths.iter().map(|path| dump_battery(path)).collect(),
+        ac_adapters: power_supply_paths
+            .iter()
+            .flat_map(|path| ac::find_sources(path))
+            .map(|source| AdapterDump {
+                power_watts: source.power_watts(),
+                name: source.name,
+                online: source.online,
+            })
+            .collect(),
+    };
+
+    let json = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| format!("Failed to serialize diagnostic bundle: {}", e))?;
+    if output == Path::new("-") {
+        println!("{}", json);
+        return Ok(());
+    }
+    fs::write(output, json + "\n")
+        .map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+    eprintln!("Wrote diagnostic bundle to {}", output.display());
+    Ok(())
+}
+
+fn dump_battery(path: &Path) -> BatteryDump {
+    let (serial_number, warnings) = match battery::Battery::new(path) {
+        Ok((battery, warnings)) => (
+            battery.serial_number,
+            warnings.iter().map(|w| w.to_string()).collect(),
+        ),
+        Err(e) => (None, vec![format!("Failed to read battery: {}", e)]),
+    };
+
+    BatteryDump {
+        name: path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        present: battery::is_present(path),
+        serial_number,
+        charge_source: about::charge_source(path),
+        threshold_scheme: about::threshold_scheme(path),
+        alarm_supported: alarm::is_supported(path),
+        attributes: battery::read_attributes(path),
+        warnings,
+    }
+}
+
+fn os_name() -> Option<String> {
+    let release = fs::read_to_string("/etc/os-release")
+        .or_else(|_| fs::read_to_string("/usr/lib/os-release"))
+        .ok()?;
+    release
+        .lines()
+        .find_map(|line| line.strip_prefix("PRETTY_NAME="))
+        .map(|name| name.trim_matches('"').to_string())
+}
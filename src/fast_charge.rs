@@ -0,0 +1,54 @@
+use crate::{lock::ThresholdLock, thresholds};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+// Names vendor drivers use for a fast-charge switch, relative to the battery
+// directory, in order of preference. Each holds 1 (on) or 0 (off).
+const CANDIDATES: &[&str] = &[
+    "fast_charge",
+    "rapid_charge",
+    "quick_charge",
+    "device/fast_charge",
+    "device/rapid_charge",
+];
+
+// Shown whenever fast charging is turned on
+pub const LONGEVITY_NOTE: &str = "faster charging at the cost of battery longevity";
+
+pub fn find(bat_path: &Path) -> Option<PathBuf> {
+    CANDIDATES
+        .iter()
+        .map(|name| bat_path.join(name))
+        .find(|path| path.exists())
+}
+
+pub fn is_supported(bat_path: &Path) -> bool {
+    find(bat_path).is_some()
+}
+
+pub fn read(bat_path: &Path) -> Result<bool, String> {
+    let path = find(bat_path)
+        .ok_or_else(|| format!("{} has no fast-charge attribute", bat_path.display()))?;
+    let raw = fs::read_to_string(&path)
+        .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    match raw.trim() {
+        "1" => Ok(true),
+        "0" => Ok(false),
+        other => Err(format!(
+            "{} holds an invalid value '{}'",
+            path.display(),
+            other
+        )),
+    }
+}
+
+pub fn write(bat_path: &Path, enabled: bool) -> Result<(), String> {
+    let path = find(bat_path)
+        .ok_or_else(|| format!("{} has no fast-charge attribute", bat_path.display()))?;
+    let _lock =
+        ThresholdLock::acquire().map_err(|e| format!("failed to lock thresholds: {}", e))?;
+    thresholds::write_attribute(&path, if enabled { "1" } else { "0" })
+        .map_err(|e| format!("failed to write {}", e))
+}
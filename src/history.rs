@@ -0,0 +1,139 @@
+use crate::battery::Battery;
+#[cfg(not(test))]
+use std::env;
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+// One entry per battery per day, so this covers several years on a dual-battery laptop
+const MAX_ENTRIES: usize = 2000;
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+pub struct HistoryEntry {
+    pub timestamp: u64,
+    pub battery: String,
+    pub cycles: Option<u32>,
+    pub health: Option<f32>,
+}
+
+impl HistoryEntry {
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split_whitespace();
+        let timestamp = fields.next()?.parse().ok()?;
+        let battery = fields.next()?.to_string();
+        let cycles = parse_optional(fields.next()?)?;
+        let health = parse_optional(fields.next()?)?;
+
+        Some(Self {
+            timestamp,
+            battery,
+            cycles,
+            health,
+        })
+    }
+
+    fn to_line(&self) -> String {
+        format!(
+            "{} {} {} {}",
+            self.timestamp,
+            self.battery,
+            self.cycles
+                .map(|c| c.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            self.health
+                .map(|h| format!("{:.1}", h))
+                .unwrap_or_else(|| "-".to_string())
+        )
+    }
+}
+
+// "-" marks a value the battery didn't report; anything else unparseable is corrupt
+fn parse_optional<T: std::str::FromStr>(field: &str) -> Option<Option<T>> {
+    if field == "-" {
+        Some(None)
+    } else {
+        field.parse().ok().map(Some)
+    }
+}
+
+#[cfg(not(test))]
+pub fn state_dir() -> Option<PathBuf> {
+    env::var_os("XDG_STATE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state")))
+        .map(|dir| dir.join("batty"))
+}
+
+// Tests keep their history, last-full markers and fallback lock in the mock tree
+#[cfg(test)]
+pub fn state_dir() -> Option<PathBuf> {
+    crate::test_support::current_root().map(|root| root.join("state"))
+}
+
+fn history_path() -> Option<PathBuf> {
+    state_dir().map(|dir| dir.join("history.log"))
+}
+
+fn read_entries() -> Vec<HistoryEntry> {
+    history_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .map(|contents| contents.lines().filter_map(HistoryEntry::parse).collect())
+        .unwrap_or_default()
+}
+
+// Oldest first; a clock that jumped back can leave the file out of order
+pub fn load(battery_name: &str) -> Vec<HistoryEntry> {
+    let mut entries: Vec<HistoryEntry> = read_entries()
+        .into_iter()
+        .filter(|entry| entry.battery == battery_name)
+        .collect();
+    entries.sort_by_key(|entry| entry.timestamp);
+    entries
+}
+
+pub fn record(battery: &Battery) -> io::Result<()> {
+    let path = history_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no state directory"))?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+
+    let mut entries = read_entries();
+    let recorded_today = entries.iter().any(|entry| {
+        entry.battery == battery.name()
+            && entry.timestamp / SECS_PER_DAY == timestamp / SECS_PER_DAY
+    });
+    if recorded_today {
+        return Ok(());
+    }
+
+    let entry = HistoryEntry {
+        timestamp,
+        battery: battery.name().to_string(),
+        cycles: battery.cycles,
+        health: battery.health(),
+    };
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    entries.push(entry);
+    if entries.len() > MAX_ENTRIES {
+        // Rewriting also drops any corrupt lines that were skipped while reading
+        let keep = &entries[entries.len() - MAX_ENTRIES..];
+        let contents: String = keep.iter().map(|e| e.to_line() + "\n").collect();
+        fs::write(&path, contents)
+    } else {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        writeln!(file, "{}", entries[entries.len() - 1].to_line())
+    }
+}
@@ -0,0 +1,305 @@
+use std::{env, fmt, sync::OnceLock};
+
+// User-facing text shown in the TUI and human-readable CLI output. JSON, CSV
+// and metrics keep their fixed English values so scripts don't break.
+#[derive(Clone, Copy)]
+pub enum Msg {
+    Charging,
+    Discharging,
+    NotCharging,
+    Full,
+    Unknown,
+    Empty,
+    Batteries,
+    Battery,
+    Charge,
+    Status,
+    Cycles,
+    Health,
+    Power,
+    Temperature,
+    Thresholds,
+    TimeLeft,
+    UntilFull,
+    ChargeLimited,
+    BayEmpty,
+    ThresholdConfiguration,
+    ReadOnly,
+    StartThreshold,
+    EndThreshold,
+    CapacityAlarm,
+    HintSwitchTabs,
+    HintBatteryList,
+    HintCompare,
+    HintHistory,
+    HintDetails,
+    HintRefresh,
+    HintCopyStatus,
+    HintToggleFooter,
+    HintFastCharge,
+    HintAdjust,
+    HintSelectThreshold,
+    HintSave,
+    HintLoadProfile,
+    SaveFailedHelp,
+    ReadOnlyNotice,
+    ReadOnlyRefused,
+    Refreshed,
+    Error,
+    Warning,
+    Note,
+    CurrentThresholds,
+    Start,
+    End,
+    ThresholdsSet,
+    LastFull,
+    ChargeType,
+    FastCharge,
+    FastChargeOn,
+    FastChargeOff,
+    SerialNumber,
+    Wear,
+    WearGood,
+    WearFair,
+    WearWorn,
+    Never,
+    JustNow,
+    // "{}" is replaced with a duration such as "3h"
+    TimeAgo,
+    UpsNoThresholds,
+    HistoryDisabled,
+    HistoryEmpty,
+    // In the next three "{}" is replaced with a key from the keymap, e.g. "h"
+    HistoryTitle,
+    PickerTitle,
+    PickerHint,
+    // The "{}" placeholders in these are filled in order by tr_args
+    ProfileLoaded,
+    PlatformProfileApplied,
+    SaveFailed,
+    FastChargeUnavailable,
+    FastChargeFailed,
+    StatusCopied,
+    StatusWritten,
+    WriteFailed,
+    AttributesTitle,
+    FullAnnotated,
+    Ups,
+    Days,
+}
+
+#[derive(Clone, Copy)]
+enum Language {
+    English,
+    Spanish,
+}
+
+static LANGUAGE: OnceLock<Language> = OnceLock::new();
+
+pub fn tr(msg: Msg) -> &'static str {
+    match *LANGUAGE.get_or_init(detect_language) {
+        Language::English => english(msg),
+        Language::Spanish => spanish(msg),
+    }
+}
+
+// Replaces the message's "{}" placeholders with `args` in order
+pub fn tr_args(msg: Msg, args: &[&dyn fmt::Display]) -> String {
+    args.iter().fold(tr(msg).to_string(), |text, arg| {
+        text.replacen("{}", &arg.to_string(), 1)
+    })
+}
+
+// Same precedence as gettext: LC_ALL, then LC_MESSAGES, then LANG
+fn detect_language() -> Language {
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default();
+    // "es_AR.UTF-8" -> "es"
+    match locale.split(['_', '.', '@']).next().unwrap_or("") {
+        "es" => Language::Spanish,
+        _ => Language::English,
+    }
+}
+
+fn english(msg: Msg) -> &'static str {
+    match msg {
+        Msg::Charging => "charging",
+        Msg::Discharging => "discharging",
+        Msg::NotCharging => "not charging",
+        Msg::Full => "full",
+        Msg::Unknown => "unknown",
+        Msg::Empty => "empty",
+        Msg::Batteries => "Batteries",
+        Msg::Battery => "Battery",
+        Msg::Charge => "Charge",
+        Msg::Status => "Status",
+        Msg::Cycles => "Cycles",
+        Msg::Health => "Health",
+        Msg::Power => "Power",
+        Msg::Temperature => "Temperature",
+        Msg::Thresholds => "Thresholds",
+        Msg::TimeLeft => "Time left",
+        Msg::UntilFull => "Until full",
+        Msg::ChargeLimited => "Charge limited",
+        Msg::BayEmpty => "Battery bay empty",
+        Msg::ThresholdConfiguration => "Threshold Configuration",
+        Msg::ReadOnly => "read-only",
+        Msg::StartThreshold => "Start threshold",
+        Msg::EndThreshold => "End threshold",
+        Msg::CapacityAlarm => "Capacity alarm",
+        Msg::HintSwitchTabs => "switch battery tabs",
+        Msg::HintBatteryList => "choose a battery from a list",
+        Msg::HintCompare => "compare all batteries side by side",
+        Msg::HintHistory => "toggle cycle/health history",
+        Msg::HintDetails => "show every attribute the driver reports",
+        Msg::HintRefresh => "refresh now",
+        Msg::HintCopyStatus => "copy status as JSON",
+        Msg::HintToggleFooter => "show/hide messages",
+        Msg::HintFastCharge => "toggle fast charging (faster, but wears the battery)",
+        Msg::HintAdjust => "adjust thresholds",
+        Msg::HintSelectThreshold => "select threshold",
+        Msg::HintSave => "save",
+        Msg::HintLoadProfile => "load a profile",
+        Msg::SaveFailedHelp => "If saving fails, rerun with sudo or adjust udev permissions.",
+        Msg::ReadOnlyNotice => "Read-only mode: thresholds are shown but cannot be changed.",
+        Msg::ReadOnlyRefused => "Read-only mode: thresholds cannot be changed",
+        Msg::Refreshed => "Refreshed",
+        Msg::Error => "Error",
+        Msg::Warning => "Warning",
+        Msg::Note => "Note",
+        Msg::CurrentThresholds => "Current battery thresholds:",
+        Msg::Start => "Start",
+        Msg::End => "End",
+        Msg::ThresholdsSet => "Battery thresholds set",
+        Msg::LastFull => "Last full",
+        Msg::ChargeType => "Charge type",
+        Msg::FastCharge => "Fast charge",
+        Msg::FastChargeOn => "on (wears the battery faster)",
+        Msg::FastChargeOff => "off",
+        Msg::SerialNumber => "Serial number",
+        Msg::Wear => "Wear",
+        Msg::WearGood => "Good",
+        Msg::WearFair => "Fair",
+        Msg::WearWorn => "Worn",
+        Msg::Never => "never",
+        Msg::JustNow => "just now",
+        Msg::TimeAgo => "{} ago",
+        Msg::UpsNoThresholds => "Charge thresholds don't apply to UPS devices",
+        Msg::HistoryDisabled => "Run batty with --history to record cycle/health history",
+        Msg::HistoryEmpty => {
+            "Not enough history yet. batty records one snapshot per day when run with --history."
+        }
+        Msg::HistoryTitle => "History ({} to close)",
+        Msg::PickerTitle => "Select battery (↑/↓, Enter to open, Esc or {} to cancel)",
+        Msg::PickerHint => "press {} to choose a battery",
+        Msg::ProfileLoaded => "Loaded the {} profile ({}-{}%); press {} to save",
+        Msg::PlatformProfileApplied => ", platform profile {}",
+        Msg::SaveFailed => "Failed to save thresholds: {}",
+        Msg::FastChargeUnavailable => "Fast charging is not available on this battery",
+        Msg::FastChargeFailed => "Failed to set fast charging: {}",
+        Msg::StatusCopied => "Copied battery status as JSON",
+        Msg::StatusWritten => "No clipboard available; status written to {}",
+        Msg::WriteFailed => "Failed to write {}: {}",
+        Msg::AttributesTitle => "Attributes ({} / {} to scroll, {} to close)",
+        Msg::FullAnnotated => "Full ({})",
+        Msg::Ups => "UPS",
+        Msg::Days => "days",
+    }
+}
+
+fn spanish(msg: Msg) -> &'static str {
+    match msg {
+        Msg::Charging => "cargando",
+        Msg::Discharging => "descargando",
+        Msg::NotCharging => "sin cargar",
+        Msg::Full => "completa",
+        Msg::Unknown => "desconocido",
+        Msg::Empty => "vacía",
+        Msg::Batteries => "Baterías",
+        Msg::Battery => "Batería",
+        Msg::Charge => "Carga",
+        Msg::Status => "Estado",
+        Msg::Cycles => "Ciclos",
+        Msg::Health => "Salud",
+        Msg::Power => "Potencia",
+        Msg::Temperature => "Temperatura",
+        Msg::Thresholds => "Umbrales",
+        Msg::TimeLeft => "Tiempo restante",
+        Msg::UntilFull => "Hasta completar",
+        Msg::ChargeLimited => "Carga limitada",
+        Msg::BayEmpty => "Bahía de batería vacía",
+        Msg::ThresholdConfiguration => "Configuración de umbrales",
+        Msg::ReadOnly => "solo lectura",
+        Msg::StartThreshold => "Umbral inicial",
+        Msg::EndThreshold => "Umbral final",
+        Msg::CapacityAlarm => "Alarma de capacidad",
+        Msg::HintSwitchTabs => "cambiar de batería",
+        Msg::HintBatteryList => "elegir una batería de la lista",
+        Msg::HintCompare => "comparar todas las baterías",
+        Msg::HintHistory => "mostrar historial de ciclos/salud",
+        Msg::HintDetails => "mostrar todos los atributos del controlador",
+        Msg::HintRefresh => "actualizar ahora",
+        Msg::HintCopyStatus => "copiar estado como JSON",
+        Msg::HintToggleFooter => "mostrar/ocultar mensajes",
+        Msg::HintFastCharge => {
+            "activar/desactivar carga rápida (más rápida, pero desgasta la batería)"
+        }
+        Msg::HintAdjust => "ajustar umbrales",
+        Msg::HintSelectThreshold => "seleccionar umbral",
+        Msg::HintSave => "guardar",
+        Msg::HintLoadProfile => "cargar un perfil",
+        Msg::SaveFailedHelp => {
+            "Si no se puede guardar, ejecuta con sudo o ajusta los permisos de udev."
+        }
+        Msg::ReadOnlyNotice => {
+            "Modo solo lectura: los umbrales se muestran pero no se pueden cambiar."
+        }
+        Msg::ReadOnlyRefused => "Modo solo lectura: no se pueden cambiar los umbrales",
+        Msg::Refreshed => "Actualizado",
+        Msg::Error => "Error",
+        Msg::Warning => "Aviso",
+        Msg::Note => "Nota",
+        Msg::CurrentThresholds => "Umbrales actuales de la batería:",
+        Msg::Start => "Inicio",
+        Msg::End => "Fin",
+        Msg::ThresholdsSet => "Umbrales de la batería configurados",
+        Msg::LastFull => "Última carga completa",
+        Msg::ChargeType => "Tipo de carga",
+        Msg::FastCharge => "Carga rápida",
+        Msg::FastChargeOn => "activada (desgasta más la batería)",
+        Msg::FastChargeOff => "desactivada",
+        Msg::SerialNumber => "Número de serie",
+        Msg::Wear => "Desgaste",
+        Msg::WearGood => "Bueno",
+        Msg::WearFair => "Moderado",
+        Msg::WearWorn => "Desgastada",
+        Msg::Never => "nunca",
+        Msg::JustNow => "ahora mismo",
+        Msg::TimeAgo => "hace {}",
+        Msg::UpsNoThresholds => "Los umbrales de carga no se aplican a los SAI",
+        Msg::HistoryDisabled => {
+            "Ejecuta batty con --history para registrar el historial de ciclos/salud"
+        }
+        Msg::HistoryEmpty => {
+            "Aún no hay suficiente historial. batty guarda una instantánea al día con --history."
+        }
+        Msg::HistoryTitle => "Historial ({} para cerrar)",
+        Msg::PickerTitle => "Elegir batería (↑/↓, Enter para abrir, Esc o {} para cancelar)",
+        Msg::PickerHint => "pulsa {} para elegir una batería",
+        Msg::ProfileLoaded => "Perfil {} cargado ({}-{}%); pulsa {} para guardar",
+        Msg::PlatformProfileApplied => ", perfil de plataforma {}",
+        Msg::SaveFailed => "No se pudieron guardar los umbrales: {}",
+        Msg::FastChargeUnavailable => "La carga rápida no está disponible en esta batería",
+        Msg::FastChargeFailed => "No se pudo cambiar la carga rápida: {}",
+        Msg::StatusCopied => "Estado de la batería copiado como JSON",
+        Msg::StatusWritten => "No hay portapapeles; estado guardado en {}",
+        Msg::WriteFailed => "No se pudo escribir {}: {}",
+        Msg::AttributesTitle => "Atributos ({} / {} para desplazar, {} para cerrar)",
+        Msg::FullAnnotated => "Completa ({})",
+        Msg::Ups => "SAI",
+        Msg::Days => "días",
+    }
+}
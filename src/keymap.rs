@@ -0,0 +1,228 @@
+use crate::battery::Warning;
+use crossterm::event::KeyCode;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    Quit,
+    Increment,
+    Decrement,
+    Save,
+    SwitchThreshold,
+    PrevTab,
+    NextTab,
+    ToggleHistory,
+    BatteryList,
+    Compare,
+    ScrollUp,
+    ScrollDown,
+    CopyStatus,
+    Refresh,
+    ToggleFooter,
+    ToggleDetails,
+    ToggleFastCharge,
+}
+
+impl Action {
+    const ALL: [Action; 17] = [
+        Action::Quit,
+        Action::Increment,
+        Action::Decrement,
+        Action::Save,
+        Action::SwitchThreshold,
+        Action::PrevTab,
+        Action::NextTab,
+        Action::ToggleHistory,
+        Action::BatteryList,
+        Action::Compare,
+        Action::ScrollUp,
+        Action::ScrollDown,
+        Action::CopyStatus,
+        Action::Refresh,
+        Action::ToggleFooter,
+        Action::ToggleDetails,
+        Action::ToggleFastCharge,
+    ];
+
+    // Name used for the action in the [keys] config table
+    fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Increment => "increment",
+            Action::Decrement => "decrement",
+            Action::Save => "save",
+            Action::SwitchThreshold => "switch_threshold",
+            Action::PrevTab => "prev_tab",
+            Action::NextTab => "next_tab",
+            Action::ToggleHistory => "toggle_history",
+            Action::BatteryList => "battery_list",
+            Action::Compare => "compare",
+            Action::ScrollUp => "scroll_up",
+            Action::ScrollDown => "scroll_down",
+            Action::CopyStatus => "copy_status",
+            Action::Refresh => "refresh",
+            Action::ToggleFooter => "toggle_footer",
+            Action::ToggleDetails => "toggle_details",
+            Action::ToggleFastCharge => "toggle_fast_charge",
+        }
+    }
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q", "Esc"],
+            Action::Increment => &["Up", "+"],
+            Action::Decrement => &["Down", "-"],
+            Action::Save => &["Enter"],
+            Action::SwitchThreshold => &["j", "k"],
+            Action::PrevTab => &["Left", "["],
+            Action::NextTab => &["Right", "]"],
+            Action::ToggleHistory => &["h"],
+            Action::BatteryList => &["b"],
+            Action::Compare => &["c"],
+            Action::ScrollUp => &["PageUp"],
+            Action::ScrollDown => &["PageDown"],
+            Action::CopyStatus => &["y"],
+            Action::Refresh => &["F5", "g"],
+            Action::ToggleFooter => &["f"],
+            Action::ToggleDetails => &["Tab"],
+            Action::ToggleFastCharge => &["F"],
+        }
+    }
+}
+
+// A binding in the config may be a single key or a list of keys
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    fn keys(&self) -> &[String] {
+        match self {
+            KeyList::One(key) => std::slice::from_ref(key),
+            KeyList::Many(keys) => keys,
+        }
+    }
+}
+
+pub struct KeyMap {
+    bindings: Vec<(Action, Vec<KeyCode>)>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        let bindings = Action::ALL
+            .iter()
+            .map(|&action| {
+                let keys = action
+                    .default_keys()
+                    .iter()
+                    .filter_map(|key| parse_key(key))
+                    .collect();
+                (action, keys)
+            })
+            .collect();
+        Self { bindings }
+    }
+}
+
+impl KeyMap {
+    // Configured actions replace their default keys; unknown action or key
+    // names are reported as warnings and otherwise ignored
+    pub fn from_config(config: &HashMap<String, KeyList>) -> (Self, Vec<Warning>) {
+        let mut keymap = Self::default();
+        let mut warnings = Vec::new();
+
+        for (name, keys) in config {
+            let Some(action) = Action::ALL.iter().find(|action| action.name() == name) else {
+                warnings.push(Warning::warn(format!(
+                    "Unknown key binding action '{}' in config",
+                    name
+                )));
+                continue;
+            };
+
+            let mut codes = Vec::new();
+            for key in keys.keys() {
+                match parse_key(key) {
+                    Some(code) => codes.push(code),
+                    None => warnings.push(Warning::warn(format!(
+                        "Unknown key '{}' for action '{}' in config",
+                        key, name
+                    ))),
+                }
+            }
+
+            // A key bound explicitly wins over the same key's default action
+            for (_, bound) in keymap.bindings.iter_mut() {
+                bound.retain(|code| !codes.contains(code));
+            }
+            if let Some((_, bound)) = keymap.bindings.iter_mut().find(|(a, _)| a == action) {
+                *bound = codes;
+            }
+        }
+
+        (keymap, warnings)
+    }
+
+    pub fn action(&self, code: KeyCode) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.contains(&code))
+            .map(|(action, _)| *action)
+    }
+
+    // e.g. "↑/+" for the footer hints
+    pub fn describe(&self, action: Action) -> String {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map(|(_, keys)| keys.iter().map(key_label).collect::<Vec<_>>().join("/"))
+            .filter(|label| !label.is_empty())
+            .unwrap_or_else(|| "(unbound)".to_string())
+    }
+}
+
+fn parse_key(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+
+    let code = match name.to_lowercase().as_str() {
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "space" => KeyCode::Char(' '),
+        "backspace" => KeyCode::Backspace,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        other => {
+            let n = other.strip_prefix('f')?.parse().ok()?;
+            KeyCode::F(n)
+        }
+    };
+    Some(code)
+}
+
+fn key_label(code: &KeyCode) -> String {
+    match code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        KeyCode::Left => "←".to_string(),
+        KeyCode::Right => "→".to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        other => format!("{:?}", other),
+    }
+}
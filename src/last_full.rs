@@ -0,0 +1,52 @@
+use crate::{
+    history,
+    i18n::{tr, Msg},
+};
+use std::{
+    fs, io,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+// One small file per battery holding the Unix time it last reached Full
+fn path(battery_name: &str) -> Option<PathBuf> {
+    history::state_dir().map(|dir| dir.join(format!("last-full-{}", battery_name)))
+}
+
+pub fn load(battery_name: &str) -> Option<u64> {
+    let contents = fs::read_to_string(path(battery_name)?).ok()?;
+    contents.trim().parse().ok()
+}
+
+pub fn record(battery_name: &str) -> io::Result<u64> {
+    let path = path(battery_name)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no state directory"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let now = now();
+    fs::write(&path, format!("{}\n", now))?;
+    Ok(now)
+}
+
+// "3h ago", "2d ago", or "never" before the first recorded full charge
+pub fn describe(timestamp: Option<u64>) -> String {
+    let Some(timestamp) = timestamp else {
+        return tr(Msg::Never).to_string();
+    };
+    let elapsed = now().saturating_sub(timestamp);
+    let amount = match elapsed {
+        0..=59 => return tr(Msg::JustNow).to_string(),
+        60..=3599 => format!("{}m", elapsed / 60),
+        3600..=86_399 => format!("{}h", elapsed / 3600),
+        _ => format!("{}d", elapsed / 86_400),
+    };
+    tr(Msg::TimeAgo).replace("{}", &amount)
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
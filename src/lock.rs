@@ -0,0 +1,74 @@
+use crate::history;
+use std::{
+    fs::{self, File, OpenOptions},
+    io,
+    path::{Path, PathBuf},
+};
+
+// Shared by every user, so a root session and one writing through a udev
+// rule or BATTY_WRITE_HELPER still wait for each other
+#[cfg(not(test))]
+const SYSTEM_LOCK: &str = "/run/batty.lock";
+// Fallback in the per-user state directory when /run isn't writable
+const LOCK_FILE: &str = "thresholds.lock";
+
+// Advisory lock held while thresholds are written so concurrent batty instances
+// (TUI, scripts, scheduled reverts) serialize instead of interleaving writes.
+// The kernel drops the lock if the process dies, so a stale file never blocks.
+pub struct ThresholdLock {
+    file: Option<File>,
+}
+
+impl ThresholdLock {
+    // Blocks until any other holder releases the lock
+    pub fn acquire() -> io::Result<Self> {
+        let Some(file) = open_lock_file()? else {
+            return Ok(Self { file: None });
+        };
+        file.lock()?;
+
+        Ok(Self { file: Some(file) })
+    }
+}
+
+// flock() works on a read-only descriptor, so a lock file root already created
+// in /run is still usable by everyone else
+fn open_lock_file() -> io::Result<Option<File>> {
+    let open = |path: &Path| {
+        OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)
+    };
+    if let Some(path) = system_lock() {
+        if let Ok(file) = open(&path).or_else(|_| File::open(&path)) {
+            return Ok(Some(file));
+        }
+    }
+
+    let Some(dir) = history::state_dir() else {
+        return Ok(None);
+    };
+    fs::create_dir_all(&dir)?;
+    open(&dir.join(LOCK_FILE)).map(Some)
+}
+
+#[cfg(not(test))]
+fn system_lock() -> Option<PathBuf> {
+    Some(PathBuf::from(SYSTEM_LOCK))
+}
+
+// Tests lock inside their mock tree, so they neither need nor block on /run
+#[cfg(test)]
+fn system_lock() -> Option<PathBuf> {
+    crate::test_support::current_root().map(|root| root.join("batty.lock"))
+}
+
+impl Drop for ThresholdLock {
+    fn drop(&mut self) {
+        if let Some(file) = &self.file {
+            let _ = file.unlock();
+        }
+    }
+}
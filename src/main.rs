@@ -1,36 +1,257 @@
+mod about;
+mod ac;
+mod alarm;
+mod bar;
 mod battery;
+mod charge_type;
 mod cli;
+mod color;
+mod config;
+mod doctor;
+mod error;
+mod export;
+mod fast_charge;
+mod history;
+mod i18n;
+mod keymap;
+mod last_full;
+mod lock;
+mod nagios;
+mod platform_profile;
+mod report;
+mod schedule;
+#[cfg(feature = "serve")]
+mod serve;
+mod service;
+mod setup;
+mod signals;
+#[cfg(test)]
+mod test_support;
+mod theme;
 mod thresholds;
 mod tui;
+mod watch;
 
-use battery::find_batteries;
 use clap::Parser;
-use cli::Cli;
-use std::path::PathBuf;
-use thresholds::{ThresholdKind, Thresholds};
+use clap_complete::CompleteEnv;
+use cli::{Cli, Command, KindArg, SwitchArg};
+use i18n::{tr, Msg};
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+use thresholds::{DischargeGuard, ThresholdKind, Thresholds};
 
 fn main() {
+    CompleteEnv::with_factory(cli::command).complete();
+
     let cli = Cli::parse();
+    color::init(cli.no_color);
+
+    let config = match config::Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    battery::set_energy_scale(config.energy_scale);
+    thresholds::set_manage_start(!cli.end_only && config.manage_start.unwrap_or(true));
+    // --force turns a blocking guard into a warning, like it skips min_end
+    thresholds::set_discharge_guard(match config.discharge_guard {
+        DischargeGuard::Block if cli.force => DischargeGuard::Warn,
+        guard => guard,
+    });
+    let read_only = cli.read_only || config.read_only;
+    if read_only && cli.requests_write() {
+        eprintln!("Error: batty is running in read-only mode; threshold changes are disabled");
+        std::process::exit(1);
+    }
+
+    let min_end = if cli.force {
+        None
+    } else {
+        Some(
+            cli.min_end
+                .or(config.min_end)
+                .unwrap_or(thresholds::DEFAULT_MIN_END),
+        )
+    };
+
+    let power_supply_paths = battery::power_supply_paths(cli.path.clone());
+    // Only user-supplied paths are checked; a missing default means no batteries
+    for path in &power_supply_paths {
+        if path == Path::new(battery::DEFAULT_POWER_SUPPLY_PATH) {
+            continue;
+        }
+        if let Err(e) = battery::check_power_supply_path(path) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+    let (mut bat_paths, discovery_warnings) = battery::find_batteries(
+        &power_supply_paths,
+        cli.include_ups,
+        cli.include_peripherals,
+    );
+    for warning in &discovery_warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    if cli.skip_empty {
+        bat_paths.retain(|path| battery::is_present(path));
+    }
+
+    if let Some(Command::About) = cli.command {
+        about::run(&power_supply_paths, &bat_paths);
+        return;
+    }
+
+    // Needs no battery, so it still works after the hardware changed
+    if let Some(Command::UninstallService) = cli.command {
+        if let Err(e) = service::uninstall() {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
 
-    let power_supply_path = cli
-        .path
-        .unwrap_or_else(|| PathBuf::from("/sys/class/power_supply"));
+    if let Some(Command::Doctor) = cli.command {
+        let ok = doctor::run(&power_supply_paths, &bat_paths, &config.wear);
+        std::process::exit(if ok { 0 } else { 1 });
+    }
 
-    let bat_paths = find_batteries(&power_supply_path);
+    if let Some(Command::Export { output }) = &cli.command {
+        if let Err(e) = export::run(&power_supply_paths, &bat_paths, output) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
 
     if bat_paths.is_empty() {
-        eprintln!("Error: No batteries found in {}", power_supply_path.display());
+        eprintln!(
+            "Error: No batteries found in {}",
+            battery::display_paths(&power_supply_paths)
+        );
         eprintln!("Make sure you're running on a laptop with battery support.");
         std::process::exit(1);
     }
 
+    let mut bat_paths = match &cli.battery {
+        Some(name) => vec![select_battery(&bat_paths, name)],
+        None => bat_paths,
+    };
+    // The configured battery goes first so CLI operations pick it, while
+    // list and the TUI still show every battery
+    if let (None, Some(name)) = (&cli.battery, &config.battery) {
+        match bat_paths
+            .iter()
+            .position(|path| path.file_name().is_some_and(|n| n == name.as_str()))
+        {
+            Some(index) => bat_paths[..=index].rotate_right(1),
+            None => eprintln!(
+                "Warning: battery '{}' from the config was not found; using {}",
+                name,
+                bat_paths[0].display()
+            ),
+        }
+    }
+
+    if cli.requests_write() && !cli.dry_run {
+        for warning in thresholds::write_access_warnings(&bat_paths[0]) {
+            match warning.severity {
+                battery::Severity::Warning => eprintln!("Warning: {}", warning),
+                battery::Severity::Info => eprintln!("Note: {}", warning),
+            }
+        }
+    }
+
+    if let Some(Command::Setup) = cli.command {
+        if let Err(e) = setup::run(&bat_paths, &config, min_end) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(Command::InstallService) = cli.command {
+        if config.thresholds.is_none() {
+            eprintln!(
+                "Error: no [thresholds] in {}; run `batty setup` first",
+                config::config_path().display()
+            );
+            std::process::exit(1);
+        }
+        if let Err(e) = service::install(&config::config_path()) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if cli.history {
+        for path in &bat_paths {
+            let recorded = battery::Battery::new(path)
+                .map_err(io::Error::from)
+                .and_then(|(battery, _)| history::record(&battery));
+            if let Err(e) = recorded {
+                eprintln!(
+                    "Warning: failed to record history for {}: {}",
+                    path.display(),
+                    e
+                );
+            }
+        }
+    }
+
     if cli.tui {
-        if cli.value.is_some() {
-            eprintln!("Error: --value cannot be used with --tui");
+        if cli.value.is_some() || cli.set_start.is_some() || cli.set_end.is_some() {
+            eprintln!("Error: --value, --set-start and --set-end cannot be used with --tui");
             std::process::exit(1);
         }
 
-        if let Err(err) = tui::run_tui(bat_paths) {
+        let (keymap, config_warnings) = keymap::KeyMap::from_config(&config.keys);
+        let options = tui::TuiOptions {
+            animations: !cli.no_animations,
+            history: cli.history,
+            min_end,
+            rounding: cli.round.or(config.round).unwrap_or_default(),
+            full_display: config.full_display,
+            theme: theme::Theme::new(cli.theme.or(config.theme).unwrap_or_default()),
+            keymap,
+            config_warnings,
+            read_only,
+            compare: cli.compare,
+            power_smoothing: config
+                .power_smoothing
+                .unwrap_or(config::DEFAULT_POWER_SMOOTHING),
+            power_samples: config
+                .power_samples
+                .unwrap_or(config::DEFAULT_POWER_SAMPLES),
+            refresh_interval: config
+                .refresh_interval
+                .unwrap_or(config::DEFAULT_REFRESH_INTERVAL),
+            adaptive_refresh: config.adaptive_refresh.map(|adaptive| {
+                (
+                    adaptive
+                        .fast
+                        .unwrap_or(config::DEFAULT_FAST_REFRESH_INTERVAL),
+                    adaptive
+                        .idle
+                        .unwrap_or(config::DEFAULT_IDLE_REFRESH_INTERVAL),
+                )
+            }),
+            health_warning: config
+                .health_warning
+                .unwrap_or(config::DEFAULT_HEALTH_WARNING),
+            profiles: config.all_profiles(),
+            platform_profile: config.platform_profile.clone(),
+            wear: config.wear,
+            hide_footer: cli.no_footer,
+        };
+
+        install_signal_handlers();
+        if let Err(err) = tui::run_tui(bat_paths, options) {
             eprintln!("Failed to run TUI: {}", err);
             std::process::exit(1);
         }
@@ -38,19 +259,347 @@ fn main() {
         return;
     }
 
-    // Use the first battery for CLI operations
+    if let Some(Command::List) = cli.command {
+        if let Err(e) = report::print_list(&bat_paths, cli.json) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    #[cfg(feature = "serve")]
+    if let Some(Command::Serve { port }) = cli.command {
+        install_signal_handlers();
+        if let Err(e) = serve::run(&bat_paths, port) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // Use the first (or --battery) battery for CLI operations
     let battery_path = &bat_paths[0];
 
-    if let Some(value) = cli.value {
-        let kind = match cli.kind.to_lowercase().as_str() {
-            "start" => ThresholdKind::Start,
-            "end" => ThresholdKind::End,
-            _ => {
-                eprintln!("Error: kind must be either 'start' or 'end'");
+    if let Some(Command::Alarm { value }) = cli.command {
+        if !alarm::is_supported(battery_path) {
+            eprintln!(
+                "Error: {} has no capacity alarm (alarm file not found)",
+                battery_path.display()
+            );
+            std::process::exit(1);
+        }
+
+        if let Some(value) = value {
+            if let Err(e) = alarm::write(battery_path, value) {
+                eprintln!("Failed to set capacity alarm: {}", e);
+                std::process::exit(1);
+            }
+            if cli.quiet {
+                return;
+            }
+        }
+
+        match alarm::read(battery_path) {
+            Ok(percent) => println!("Capacity alarm: {}%", percent),
+            Err(e) => {
+                eprintln!("Failed to read capacity alarm: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if let Some(Command::ChargeType { value }) = &cli.command {
+        if !charge_type::is_supported(battery_path) {
+            eprintln!(
+                "Error: {} has no charge type (charge_type file not found)",
+                battery_path.display()
+            );
+            std::process::exit(1);
+        }
+
+        if let Some(value) = value {
+            if let Err(e) = charge_type::write(battery_path, value) {
+                eprintln!("Failed to set charge type: {}", e);
                 std::process::exit(1);
             }
+            if cli.quiet {
+                return;
+            }
+        }
+
+        match charge_type::read(battery_path) {
+            Ok(current) => {
+                println!("Charge type: {}", current);
+                println!(
+                    "Accepted values: {}",
+                    charge_type::available(battery_path).join(", ")
+                );
+            }
+            Err(e) => {
+                eprintln!("Failed to read charge type: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if let Some(Command::FastCharge { state }) = cli.command {
+        if !fast_charge::is_supported(battery_path) {
+            eprintln!(
+                "Error: {} has no fast-charge switch (no fast_charge or vendor attribute found)",
+                battery_path.display()
+            );
+            std::process::exit(1);
+        }
+
+        if let Some(state) = state {
+            let enable = match state {
+                SwitchArg::On => true,
+                SwitchArg::Off => false,
+                SwitchArg::Toggle => match fast_charge::read(battery_path) {
+                    Ok(enabled) => !enabled,
+                    Err(e) => {
+                        eprintln!("Failed to read fast charging: {}", e);
+                        std::process::exit(1);
+                    }
+                },
+            };
+            if let Err(e) = fast_charge::write(battery_path, enable) {
+                eprintln!("Failed to set fast charging: {}", e);
+                std::process::exit(1);
+            }
+            if cli.quiet {
+                return;
+            }
+        }
+
+        match fast_charge::read(battery_path) {
+            Ok(true) => println!("Fast charging: on ({})", fast_charge::LONGEVITY_NOTE),
+            Ok(false) => println!("Fast charging: off"),
+            Err(e) => {
+                eprintln!("Failed to read fast charging: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if let Some(Command::SetUntil {
+        value,
+        duration,
+        foreground,
+    }) = cli.command
+    {
+        let Some(kind) = cli.kind.single() else {
+            eprintln!("Error: set-until changes a single threshold; use --kind start or end");
+            std::process::exit(1);
         };
+        let configured = config.thresholds.map(|preset| match kind {
+            ThresholdKind::Start => preset.start,
+            ThresholdKind::End => preset.end,
+        });
+        if foreground {
+            install_signal_handlers();
+        }
+        if let Err(e) = schedule::set_until(
+            battery_path,
+            kind,
+            value,
+            duration,
+            foreground,
+            min_end,
+            configured,
+        ) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
 
+    if cli.watch {
+        // Checked once up front so a bad config fails now, not on the first replug
+        let reapply = cli.reapply_on_ac.then(|| {
+            let Some(preset) = config.thresholds else {
+                eprintln!(
+                    "Error: --reapply-on-ac needs [thresholds] in {}; run `batty setup` first",
+                    config::config_path().display()
+                );
+                std::process::exit(1);
+            };
+            let mut thresholds = Thresholds::default();
+            if let Err(e) = thresholds.set_both(preset.start, preset.end) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            check_min_end(&thresholds, min_end);
+            thresholds
+        });
+        let watched = if cli.all {
+            bat_paths.as_slice()
+        } else {
+            std::slice::from_ref(battery_path)
+        };
+        install_signal_handlers();
+        if let Err(e) = watch::run(
+            watched,
+            cli.interval,
+            cli.csv.as_deref(),
+            config.critical_action().as_ref(),
+            match (cli.json, cli.all_samples) {
+                (false, _) => watch::Output::Text,
+                (true, false) => watch::Output::JsonEvents,
+                (true, true) => watch::Output::JsonSamples,
+            },
+            reapply.as_ref(),
+        ) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if cli.percent {
+        match battery::Battery::new(battery_path).map(|(battery, _)| battery.percentage()) {
+            // Truncated, so 79.6% doesn't claim a level the battery hasn't reached
+            Ok(Some(percentage)) => println!("{}", percentage.floor()),
+            Ok(None) => {
+                eprintln!(
+                    "Error: charge level of {} is unknown",
+                    battery_path.display()
+                );
+                std::process::exit(1);
+            }
+            Err(e) => {
+                eprintln!("Error: failed to read battery: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if cli.check_support {
+        let name = battery_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let result = if read_only {
+            Err("read-only mode".to_string())
+        } else if !thresholds::is_supported(battery_path) {
+            Err("no threshold support".to_string())
+        } else {
+            thresholds::check_writable(battery_path)
+                .map_err(|e| format!("thresholds are not writable: {}", e))
+        };
+        match result {
+            Ok(()) => {
+                if cli.verbose {
+                    println!("{}: thresholds are writable", name);
+                }
+            }
+            Err(reason) => {
+                if cli.verbose {
+                    println!("{}: {}", name, reason);
+                }
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if cli.nagios {
+        std::process::exit(nagios::check(battery_path, &config.nagios));
+    }
+
+    if cli.bar {
+        let result = if cli.follow {
+            install_signal_handlers();
+            bar::follow(battery_path, cli.interval)
+        } else {
+            bar::print(battery_path)
+        };
+        if let Err(e) = result {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if cli.stats {
+        if let Err(e) = report::print_stats(battery_path) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(target) = cli.wait_until {
+        install_signal_handlers();
+        match watch::wait_until(
+            battery_path,
+            target,
+            cli.below,
+            cli.interval,
+            cli.timeout,
+            cli.quiet,
+        ) {
+            Ok(watch::WaitOutcome::Reached) => return,
+            Ok(watch::WaitOutcome::TimedOut) => {
+                eprintln!(
+                    "Error: timed out waiting for the charge to reach {}%",
+                    target
+                );
+                std::process::exit(2);
+            }
+            Ok(watch::WaitOutcome::Interrupted) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let preset = if cli.longevity {
+        let (start, end) = config
+            .longevity
+            .map(|p| (p.start, p.end))
+            .unwrap_or(thresholds::LONGEVITY_PRESET);
+        Some(("the longevity preset", start, end))
+    } else if cli.maximize_capacity {
+        let (start, end) = thresholds::MAXIMIZE_CAPACITY_PRESET;
+        Some(("the maximize-capacity preset", start, end))
+    } else if let Some(Command::Apply) = cli.command {
+        match config.thresholds {
+            Some(t) => Some(("the thresholds from the config", t.start, t.end)),
+            None => {
+                eprintln!(
+                    "Error: no [thresholds] in {}; run `batty setup` first",
+                    config::config_path().display()
+                );
+                std::process::exit(1);
+            }
+        }
+    } else {
+        None
+    };
+
+    let end_wh = cli.end_wh.map(
+        |wh| match thresholds::percent_of_full_energy(battery_path, wh) {
+            Ok(converted) => converted,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+    );
+    let set_end = cli.set_end.or(end_wh.map(|(percent, _)| percent));
+
+    if cli.set_start.is_some() && !thresholds::manages_start() {
+        eprintln!("Error: --set-start cannot be used when manage_start = false");
+        std::process::exit(1);
+    }
+
+    if preset.is_some() || cli.set_start.is_some() || set_end.is_some() {
         let mut thresholds = match Thresholds::load(battery_path) {
             Ok(t) => t,
             Err(e) => {
@@ -59,23 +608,106 @@ fn main() {
             }
         };
 
-        if let Err(e) = thresholds.set(kind, value) {
+        let before = thresholds;
+        let (start, end) = match preset {
+            Some((_, start, end)) => (start, end),
+            None => (
+                cli.set_start.unwrap_or(thresholds.start),
+                set_end.unwrap_or(thresholds.end),
+            ),
+        };
+        if let Err(e) = thresholds.set_both(start, end) {
             eprintln!("Error: {}", e);
             std::process::exit(1);
         }
+        check_min_end(&thresholds, min_end);
 
-        if let Err(e) = thresholds.save(battery_path) {
-            eprintln!("Failed to save thresholds: {}", e);
+        if !save_thresholds(&cli, battery_path, &before, &thresholds) {
+            return;
+        }
+
+        if !cli.quiet {
+            match preset {
+                _ if cli.diff => {}
+                Some((what, _, _)) => println!("Applied {}", what),
+                None => println!("{}", tr(Msg::ThresholdsSet)),
+            }
+            if let (Some(wh), Some((percent, full_wh))) = (cli.end_wh, end_wh) {
+                println!(
+                    "End threshold: {:.1} Wh = {}% of {:.1} Wh",
+                    wh, percent, full_wh
+                );
+            }
+            print_current_thresholds(battery_path);
+        }
+        apply_platform_profile(&cli, &config);
+        return;
+    }
+
+    if cli.kind == KindArg::Both && cli.value.is_some() && !thresholds::manages_start() {
+        eprintln!("Error: --kind both cannot be used when manage_start = false");
+        std::process::exit(1);
+    }
+
+    if cli.margin.is_some() && cli.kind != KindArg::Both {
+        eprintln!("Error: --margin requires --kind both");
+        std::process::exit(1);
+    }
+
+    if let Some(value) = cli.value {
+        let mut thresholds = match Thresholds::load(battery_path) {
+            Ok(t) => t,
+            Err(e) => {
+                eprintln!("Failed to load current thresholds: {}", e);
+                std::process::exit(1);
+            }
+        };
+
+        let before = thresholds;
+        let result = match cli.kind.single() {
+            Some(kind) => thresholds.set(kind, value),
+            None => match cli.margin {
+                Some(margin) => thresholds::window(value, margin)
+                    .and_then(|(start, end)| thresholds.set_both(start, end)),
+                None => Err("--kind both requires --margin".to_string()),
+            },
+        };
+        if let Err(e) = result {
+            eprintln!("Error: {}", e);
             std::process::exit(1);
         }
+        check_min_end(&thresholds, min_end);
+
+        if !save_thresholds(&cli, battery_path, &before, &thresholds) {
+            return;
+        }
 
-        println!("Battery charge {} threshold set to {}%", kind, value);
+        if !cli.quiet {
+            match cli.kind.single() {
+                _ if cli.diff => {}
+                Some(kind) => println!("Battery charge {} threshold set to {}%", kind, value),
+                None => println!(
+                    "Battery charge window set to {}%-{}%",
+                    thresholds.start, thresholds.end
+                ),
+            }
+            print_current_thresholds(battery_path);
+        }
+        apply_platform_profile(&cli, &config);
+    } else if cli.json {
+        if let Err(e) = report::print_json(battery_path) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
     } else {
         match Thresholds::load(battery_path) {
             Ok(thresholds) => {
-                println!("Current battery thresholds:");
-                println!("  Start: {}%", thresholds.start);
-                println!("  End:   {}%", thresholds.end);
+                let start = format!("{}:", tr(Msg::Start));
+                let end = format!("{}:", tr(Msg::End));
+                let width = start.chars().count().max(end.chars().count());
+                println!("{}", tr(Msg::CurrentThresholds));
+                println!("  {:<width$} {}%", start, thresholds.start);
+                println!("  {:<width$} {}%", end, thresholds.end);
             }
             Err(e) => {
                 eprintln!("Failed to read thresholds: {}", e);
@@ -84,3 +716,107 @@ fn main() {
         }
     }
 }
+
+// Prints the --diff/--dry-run preview and writes the new thresholds unless
+// that's not wanted; returns whether they were written
+fn save_thresholds(
+    cli: &Cli,
+    battery_path: &Path,
+    before: &Thresholds,
+    after: &Thresholds,
+) -> bool {
+    if cli.diff || cli.dry_run {
+        let show_start = thresholds::manages_start();
+        for (name, old, new, shown) in [
+            ("start", before.start, after.start, show_start),
+            ("end", before.end, after.end, true),
+        ] {
+            if !shown {
+                continue;
+            }
+            if old == new {
+                println!("{}: {}% (no change)", name, old);
+            } else {
+                println!("{}: {}% → {}%", name, old, new);
+            }
+        }
+    }
+    if cli.dry_run {
+        println!("Dry run: nothing was written");
+        return false;
+    }
+    if cli.diff && before == after {
+        return false;
+    }
+
+    match thresholds::check_discharge_guard(battery_path) {
+        Ok(Some(warning)) => eprintln!("Warning: {}", warning),
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+    if let Err(e) = after.save(battery_path) {
+        eprintln!("Failed to save thresholds: {}", e);
+        std::process::exit(1);
+    }
+    true
+}
+
+// The thresholds are already applied by now, so a profile failure only warns
+fn apply_platform_profile(cli: &Cli, config: &config::Config) {
+    if let Some(profile) = &config.platform_profile {
+        match platform_profile::apply(profile) {
+            Ok(()) if !cli.quiet => println!("Platform profile set to {}", profile),
+            Ok(()) => {}
+            Err(e) => eprintln!("Warning: {}", e),
+        }
+    }
+}
+
+// Only the long-running loops poll the termination flag; everything else
+// (setup prompts, a blocked ThresholdLock::acquire) keeps the default
+// behavior so Ctrl-C still kills it
+fn install_signal_handlers() {
+    if let Err(e) = signals::install() {
+        eprintln!("Warning: failed to install signal handlers: {}", e);
+    }
+}
+
+fn check_min_end(thresholds: &Thresholds, min_end: Option<u8>) {
+    if let Some(Err(e)) = min_end.map(|min| thresholds.check_min_end(min)) {
+        eprintln!("Error: {} (use --force to apply it anyway)", e);
+        std::process::exit(1);
+    }
+}
+
+// Reloads after a save so the output reflects what the hardware accepted
+fn print_current_thresholds(battery_path: &Path) {
+    match Thresholds::load(battery_path) {
+        Ok(thresholds) => println!("start: {}%, end: {}%", thresholds.start, thresholds.end),
+        Err(e) => eprintln!("Failed to reload thresholds: {}", e),
+    }
+}
+
+fn select_battery(bat_paths: &[PathBuf], name: &str) -> PathBuf {
+    match bat_paths
+        .iter()
+        .find(|path| path.file_name().is_some_and(|n| n == name))
+    {
+        Some(path) => path.clone(),
+        None => {
+            let names: Vec<_> = bat_paths
+                .iter()
+                .filter_map(|path| path.file_name())
+                .map(|n| n.to_string_lossy())
+                .collect();
+            eprintln!(
+                "Error: battery '{}' not found (available: {})",
+                name,
+                names.join(", ")
+            );
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use crate::battery::{Battery, BatteryStatus};
+use serde::Deserialize;
+use std::path::Path;
+
+// Monitoring-plugin exit codes
+const OK: i32 = 0;
+const WARNING: i32 = 1;
+const CRITICAL: i32 = 2;
+const UNKNOWN: i32 = 3;
+
+// Health is a percentage of the design capacity. The charge limits only apply
+// while discharging, since a low charge on AC is already being fixed.
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NagiosLimits {
+    pub health_warning: u8,
+    pub health_critical: u8,
+    pub charge_warning: u8,
+    pub charge_critical: u8,
+}
+
+impl Default for NagiosLimits {
+    fn default() -> Self {
+        Self {
+            health_warning: 80,
+            health_critical: 60,
+            charge_warning: 20,
+            charge_critical: 10,
+        }
+    }
+}
+
+// Prints the one-line plugin output ("OK - BAT0 health 92%, charge 64%" plus
+// perfdata, whose "80:" ranges alert below the value) and returns the exit code
+pub fn check(battery_path: &Path, limits: &NagiosLimits) -> i32 {
+    let battery = match Battery::new(battery_path) {
+        Ok((battery, _)) => battery,
+        Err(e) => {
+            println!("UNKNOWN - failed to read battery: {}", e);
+            return UNKNOWN;
+        }
+    };
+
+    let health = battery.health();
+    let charge = battery.percentage();
+    let discharging = battery.status == BatteryStatus::Discharging;
+    let state = level(health, limits.health_warning, limits.health_critical).max(level(
+        charge.filter(|_| discharging),
+        limits.charge_warning,
+        limits.charge_critical,
+    ));
+
+    let label = match state {
+        OK => "OK",
+        WARNING => "WARNING",
+        _ => "CRITICAL",
+    };
+    let mut perfdata = Vec::new();
+    if let Some(health) = health {
+        perfdata.push(format!(
+            "health={:.0}%;{}:;{}:",
+            health, limits.health_warning, limits.health_critical
+        ));
+    }
+    if let Some(charge) = charge {
+        perfdata.push(format!(
+            "charge={:.0}%;{}:;{}:",
+            charge, limits.charge_warning, limits.charge_critical
+        ));
+    }
+    println!(
+        "{} - {} health {}, charge {} | {}",
+        label,
+        battery.name(),
+        percent(health),
+        percent(charge),
+        perfdata.join(" ")
+    );
+    state
+}
+
+// Lower is worse for both health and charge; unknown values can't be judged
+fn level(value: Option<f32>, warning: u8, critical: u8) -> i32 {
+    match value {
+        Some(value) if value < f32::from(critical) => CRITICAL,
+        Some(value) if value < f32::from(warning) => WARNING,
+        _ => OK,
+    }
+}
+
+fn percent(value: Option<f32>) -> String {
+    value.map_or_else(|| "unknown".to_string(), |value| format!("{:.0}%", value))
+}
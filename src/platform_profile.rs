@@ -0,0 +1,45 @@
+use crate::thresholds;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+const DEFAULT_ACPI_PATH: &str = "/sys/firmware/acpi";
+
+// BATTY_ACPI_PATH points this at a mock tree, like BATTY_POWER_SUPPLY_PATH
+fn acpi_path() -> PathBuf {
+    env::var_os("BATTY_ACPI_PATH")
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_ACPI_PATH))
+}
+
+fn choices(acpi_path: &Path) -> Result<Vec<String>, String> {
+    let path = acpi_path.join("platform_profile_choices");
+    fs::read_to_string(&path)
+        .map(|raw| raw.split_whitespace().map(str::to_string).collect())
+        .map_err(|e| format!("failed to read {}: {}", path.display(), e))
+}
+
+// Written after the thresholds, e.g. "low-power" while the charge is limited
+pub fn apply(profile: &str) -> Result<(), String> {
+    let acpi_path = acpi_path();
+    let path = acpi_path.join("platform_profile");
+    if !path.exists() {
+        return Err(format!(
+            "{} not found; this machine doesn't support ACPI platform profiles",
+            path.display()
+        ));
+    }
+
+    let choices = choices(&acpi_path)?;
+    if !choices.iter().any(|choice| choice == profile) {
+        return Err(format!(
+            "invalid platform_profile '{}', expected one of: {}",
+            profile,
+            choices.join(", ")
+        ));
+    }
+
+    thresholds::write_attribute(&path, profile).map_err(|e| format!("failed to write {}", e))
+}
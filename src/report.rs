@@ -0,0 +1,217 @@
+use crate::{
+    battery::Battery,
+    charge_type,
+    color::{self, Color},
+    i18n::{tr, Msg},
+    thresholds::{self, Thresholds},
+};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+pub struct BatteryReport {
+    pub name: String,
+    pub percentage: Option<f32>,
+    pub status: String,
+    pub thresholds: Option<ThresholdReport>,
+    pub health: Option<f32>,
+    pub threshold_supported: bool,
+    pub present: bool,
+    pub device_type: String,
+    pub charge_type: Option<String>,
+    pub serial_number: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ThresholdReport {
+    pub start: u8,
+    pub end: u8,
+}
+
+#[derive(Serialize)]
+struct BatteryDocument<'a> {
+    schema_version: u32,
+    #[serde(flatten)]
+    battery: &'a BatteryReport,
+}
+
+#[derive(Serialize)]
+struct BatteryList<'a> {
+    schema_version: u32,
+    batteries: &'a [BatteryReport],
+}
+
+impl BatteryReport {
+    pub fn from_battery(battery: &Battery, path: &Path) -> Self {
+        Self {
+            name: battery.name().to_string(),
+            percentage: battery.percentage(),
+            status: if battery.present {
+                battery.status.as_str().to_string()
+            } else {
+                "empty".to_string()
+            },
+            thresholds: Thresholds::load(path).ok().map(|t| ThresholdReport {
+                start: t.start,
+                end: t.end,
+            }),
+            health: battery.health(),
+            threshold_supported: thresholds::is_supported(path),
+            present: battery.present,
+            device_type: if battery.ups { "ups" } else { "battery" }.to_string(),
+            charge_type: charge_type::read(path).ok(),
+            serial_number: battery.serial_number.clone(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let (battery, _) = Battery::new(path)
+            .map_err(|e| format!("Failed to read battery {}: {}", path.display(), e))?;
+        Ok(Self::from_battery(&battery, path))
+    }
+}
+
+pub fn print_json(path: &Path) -> Result<(), String> {
+    let report = BatteryReport::load(path)?;
+    println!("{}", battery_json(&report)?);
+    Ok(())
+}
+
+pub fn battery_json(report: &BatteryReport) -> Result<String, String> {
+    let document = BatteryDocument {
+        schema_version: SCHEMA_VERSION,
+        battery: report,
+    };
+    serde_json::to_string_pretty(&document)
+        .map_err(|e| format!("Failed to serialize battery status: {}", e))
+}
+
+pub fn load_all(bat_paths: &[PathBuf]) -> Result<Vec<BatteryReport>, String> {
+    bat_paths
+        .iter()
+        .map(|path| BatteryReport::load(path))
+        .collect()
+}
+
+pub fn list_json(reports: &[BatteryReport]) -> Result<String, String> {
+    let list = BatteryList {
+        schema_version: SCHEMA_VERSION,
+        batteries: reports,
+    };
+    serde_json::to_string_pretty(&list)
+        .map_err(|e| format!("Failed to serialize battery list: {}", e))
+}
+
+pub fn print_list(bat_paths: &[PathBuf], json: bool) -> Result<(), String> {
+    let reports = load_all(bat_paths)?;
+
+    if json {
+        println!("{}", list_json(&reports)?);
+        return Ok(());
+    }
+
+    for report in &reports {
+        // Pad before painting so escape codes don't throw off the alignment
+        let percentage = report
+            .percentage
+            .map(|p| {
+                color::paint(
+                    &format!("{:>7}", format!("{:.0}%", p)),
+                    Color::for_percentage(p),
+                )
+            })
+            .unwrap_or_else(|| format!("{:>7}", "unknown"));
+        let thresholds = match &report.thresholds {
+            Some(t) => format!("{}%-{}%", t.start, t.end),
+            None if report.device_type == "ups" => "n/a (UPS)".to_string(),
+            None => "unsupported".to_string(),
+        };
+        let health = report
+            .health
+            .map(|h| format!("{:.0}%", h))
+            .unwrap_or_else(|| "unknown".to_string());
+
+        println!(
+            "{:<8} {}  {:<13} thresholds: {:<11} health: {}",
+            report.name, percentage, report.status, thresholds, health
+        );
+    }
+
+    Ok(())
+}
+
+// Non-interactive counterpart of the TUI header for quick terminal checks
+pub fn print_stats(path: &Path) -> Result<(), String> {
+    let (battery, _) = Battery::new(path)
+        .map_err(|e| format!("Failed to read battery {}: {}", path.display(), e))?;
+    let unknown = || tr(Msg::Unknown).to_string();
+
+    let charge = battery
+        .percentage()
+        .map(|p| color::paint(&format!("{:.2}%", p), Color::for_percentage(p)))
+        .unwrap_or_else(unknown);
+    let status = if battery.present {
+        battery.status.label()
+    } else {
+        tr(Msg::Empty)
+    };
+    let power = battery
+        .power_watts()
+        .map(|w| format!("{:.2} W", w))
+        .unwrap_or_else(unknown);
+    let thresholds = match Thresholds::load(path) {
+        Ok(t) => format!("{}%-{}%", t.start, t.end),
+        Err(_) if battery.ups => "n/a (UPS)".to_string(),
+        Err(_) => "unsupported".to_string(),
+    };
+    let name = if battery.ups {
+        format!("{} (UPS)", battery.name())
+    } else {
+        battery.name().to_string()
+    };
+
+    let mut rows = vec![
+        (tr(Msg::Battery), name),
+        (tr(Msg::Charge), charge),
+        (tr(Msg::Status), status.to_string()),
+        (tr(Msg::Power), power),
+        (
+            tr(Msg::Cycles),
+            battery
+                .cycles
+                .map(|c| c.to_string())
+                .unwrap_or_else(unknown),
+        ),
+        (
+            tr(Msg::Health),
+            battery
+                .health()
+                .map(|h| format!("{:.0}%", h))
+                .unwrap_or_else(unknown),
+        ),
+        (
+            tr(Msg::Temperature),
+            battery
+                .temperature
+                .map(|t| format!("{:.1} °C", t))
+                .unwrap_or_else(unknown),
+        ),
+        (tr(Msg::Thresholds), thresholds),
+    ];
+    if let Ok(charge_type) = charge_type::read(path) {
+        rows.push((tr(Msg::ChargeType), charge_type));
+    }
+    if let Some(serial) = &battery.serial_number {
+        rows.push((tr(Msg::SerialNumber), serial.clone()));
+    }
+    let width = rows
+        .iter()
+        .map(|(label, _)| label.chars().count())
+        .fold(12, usize::max);
+    for (label, value) in rows {
+        println!("{:<width$} {}", label, value);
+    }
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use crate::{
+    signals,
+    thresholds::{self, ThresholdKind, Thresholds},
+};
+use std::{
+    env, io,
+    path::Path,
+    process::{self, Command},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+// `configured` is this threshold's value from [thresholds] in the config; the
+// revert goes back to it, or to the value found before the change without one
+pub fn set_until(
+    battery_path: &Path,
+    kind: ThresholdKind,
+    value: u8,
+    duration: Duration,
+    foreground: bool,
+    min_end: Option<u8>,
+    configured: Option<u8>,
+) -> Result<(), String> {
+    let mut thresholds = Thresholds::load(battery_path)
+        .map_err(|e| format!("Failed to load current thresholds: {}", e))?;
+    let previous = thresholds.get(kind);
+    let target = configured.unwrap_or(previous);
+
+    thresholds.set(kind, value)?;
+    if let Some(min_end) = min_end {
+        thresholds
+            .check_min_end(min_end)
+            .map_err(|e| format!("{} (use --force to apply it anyway)", e))?;
+    }
+    if let Some(warning) = thresholds::check_discharge_guard(battery_path)? {
+        eprintln!("Warning: {}", warning);
+    }
+    thresholds
+        .save(battery_path)
+        .map_err(|e| format!("Failed to save thresholds: {}", e))?;
+    println!(
+        "Battery charge {} threshold set to {}% for {}s (was {}%)",
+        kind,
+        value,
+        duration.as_secs(),
+        previous
+    );
+
+    if foreground {
+        // Keep the process alive and restore the old value ourselves. On
+        // SIGTERM/SIGINT revert early rather than leaving the override behind.
+        if !signals::sleep(duration) {
+            println!("Interrupted, reverting early");
+        }
+        revert(battery_path, kind, target)
+    } else {
+        let unit = schedule_revert(battery_path, kind, target, duration)
+            .map_err(|e| format!("Failed to schedule revert with systemd-run: {}", e))?;
+        println!(
+            "Scheduled revert to {}% in {}s via the transient systemd timer {}",
+            target,
+            duration.as_secs(),
+            unit
+        );
+        Ok(())
+    }
+}
+
+fn revert(battery_path: &Path, kind: ThresholdKind, target: u8) -> Result<(), String> {
+    let mut thresholds = Thresholds::load(battery_path)
+        .map_err(|e| format!("Failed to load current thresholds: {}", e))?;
+    thresholds.set(kind, target)?;
+    thresholds
+        .save(battery_path)
+        .map_err(|e| format!("Failed to save thresholds: {}", e))?;
+    println!("Battery charge {} threshold reverted to {}%", kind, target);
+    Ok(())
+}
+
+// The battery directory itself is passed as --path, so the revert hits this
+// battery even when the power supply directory holds several. The unit name
+// is unique so a second set-until doesn't clash with a revert still pending.
+// Returns the unit name.
+fn schedule_revert(
+    battery_path: &Path,
+    kind: ThresholdKind,
+    target: u8,
+    duration: Duration,
+) -> io::Result<String> {
+    let exe = env::current_exe()?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let unit = format!("batty-revert-{}-{}-{}", kind, now, process::id());
+    let status = Command::new("systemd-run")
+        .arg(format!("--on-active={}s", duration.as_secs()))
+        .arg(format!("--unit={}", unit))
+        .arg(exe)
+        .arg("--path")
+        .arg(battery_path)
+        .arg("--kind")
+        .arg(kind.to_string())
+        .arg("--value")
+        .arg(target.to_string())
+        // Restoring the old value must not be held up by the discharge guard
+        .arg("--force")
+        .status()?;
+
+    if status.success() {
+        Ok(unit)
+    } else {
+        Err(io::Error::other(format!(
+            "systemd-run exited with {}",
+            status
+        )))
+    }
+}
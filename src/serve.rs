@@ -0,0 +1,106 @@
+use crate::{
+    report::{self, BatteryReport},
+    signals,
+};
+use std::{fmt::Write, path::PathBuf, time::Duration};
+use tiny_http::{Header, Response, Server};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+pub fn run(bat_paths: &[PathBuf], port: u16) -> Result<(), String> {
+    let server = Server::http(("0.0.0.0", port))
+        .map_err(|e| format!("Failed to listen on port {}: {}", port, e))?;
+    println!("Serving battery status on http://0.0.0.0:{}", port);
+
+    // Poll with a timeout so a pending SIGTERM is noticed between requests
+    while !signals::terminate_requested() {
+        let request = match server.recv_timeout(POLL_INTERVAL) {
+            Ok(Some(request)) => request,
+            Ok(None) => continue,
+            Err(e) => return Err(format!("Failed to accept request: {}", e)),
+        };
+
+        // Batteries are re-read on every request so clients never see stale data
+        let (status, content_type, body) = match request.url() {
+            "/" | "/status" => {
+                match report::load_all(bat_paths).and_then(|r| report::list_json(&r)) {
+                    Ok(json) => (200, "application/json", json),
+                    Err(e) => (500, "text/plain", e),
+                }
+            }
+            "/metrics" => match report::load_all(bat_paths) {
+                Ok(reports) => (200, "text/plain; version=0.0.4", metrics(&reports)),
+                Err(e) => (500, "text/plain", e),
+            },
+            _ => (404, "text/plain", "not found".to_string()),
+        };
+
+        let header = Header::from_bytes("Content-Type", content_type)
+            .expect("static content type header is valid");
+        let response = Response::from_string(body)
+            .with_status_code(status)
+            .with_header(header);
+        if let Err(e) = request.respond(response) {
+            eprintln!("Failed to send response: {}", e);
+        }
+    }
+
+    println!("Shutting down");
+    Ok(())
+}
+
+type Gauge = (
+    &'static str,
+    &'static str,
+    fn(&BatteryReport) -> Option<f32>,
+);
+
+fn metrics(reports: &[BatteryReport]) -> String {
+    let mut out = String::new();
+
+    let gauges: [Gauge; 4] = [
+        ("batty_charge_percent", "Battery charge in percent", |r| {
+            r.percentage
+        }),
+        (
+            "batty_health_percent",
+            "Full capacity relative to design capacity in percent",
+            |r| r.health,
+        ),
+        (
+            "batty_threshold_start_percent",
+            "Charge start threshold in percent",
+            |r| r.thresholds.as_ref().map(|t| f32::from(t.start)),
+        ),
+        (
+            "batty_threshold_end_percent",
+            "Charge end threshold in percent",
+            |r| r.thresholds.as_ref().map(|t| f32::from(t.end)),
+        ),
+    ];
+
+    for (name, help, value) in gauges {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} gauge", name);
+        for report in reports {
+            if let Some(value) = value(report) {
+                let _ = writeln!(out, "{}{{battery=\"{}\"}} {}", name, report.name, value);
+            }
+        }
+    }
+
+    let _ = writeln!(
+        out,
+        "# HELP batty_status Current charging status of the battery"
+    );
+    let _ = writeln!(out, "# TYPE batty_status gauge");
+    for report in reports {
+        let _ = writeln!(
+            out,
+            "batty_status{{battery=\"{}\",status=\"{}\"}} 1",
+            report.name, report.status
+        );
+    }
+
+    out
+}
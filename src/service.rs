@@ -0,0 +1,88 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+pub const UNIT_NAME: &str = "batty.service";
+const UNIT_DIR: &str = "/etc/systemd/system";
+
+pub fn unit_path() -> PathBuf {
+    Path::new(UNIT_DIR).join(UNIT_NAME)
+}
+
+// A oneshot unit that runs `batty apply` at boot with the given config file
+fn unit_contents(exe: &Path, config_path: &Path) -> String {
+    format!(
+        "[Unit]\n\
+         Description=Apply battery charge thresholds (batty)\n\
+         After=multi-user.target\n\
+         \n\
+         [Service]\n\
+         Type=oneshot\n\
+         Environment=BATTY_CONFIG={}\n\
+         ExecStart={} apply --quiet\n\
+         \n\
+         [Install]\n\
+         WantedBy=multi-user.target\n",
+        config_path.display(),
+        exe.display()
+    )
+}
+
+pub fn install(config_path: &Path) -> Result<(), String> {
+    let exe =
+        env::current_exe().map_err(|e| format!("Failed to locate the batty executable: {}", e))?;
+    let config_path = fs::canonicalize(config_path).unwrap_or_else(|_| config_path.to_path_buf());
+    let unit_path = unit_path();
+
+    fs::write(&unit_path, unit_contents(&exe, &config_path))
+        .map_err(|e| format!("Failed to write {}: {}", unit_path.display(), e))?;
+    println!("Wrote {}", unit_path.display());
+
+    systemctl(&["daemon-reload"])?;
+    systemctl(&["enable", "--now", UNIT_NAME])?;
+    println!(
+        "Enabled {}; thresholds will be re-applied at boot",
+        UNIT_NAME
+    );
+    Ok(())
+}
+
+pub fn uninstall() -> Result<(), String> {
+    let unit_path = unit_path();
+    if !unit_path.exists() {
+        println!(
+            "{} is not installed ({} not found); nothing to do",
+            UNIT_NAME,
+            unit_path.display()
+        );
+        return Ok(());
+    }
+
+    systemctl(&["stop", UNIT_NAME])?;
+    println!("Stopped {}", UNIT_NAME);
+    systemctl(&["disable", UNIT_NAME])?;
+    println!("Disabled {}", UNIT_NAME);
+    fs::remove_file(&unit_path)
+        .map_err(|e| format!("Failed to remove {}: {}", unit_path.display(), e))?;
+    println!("Removed {}", unit_path.display());
+    systemctl(&["daemon-reload"])?;
+    println!("Reloaded systemd; thresholds will no longer be applied at boot");
+    Ok(())
+}
+
+fn systemctl(args: &[&str]) -> Result<(), String> {
+    let status = Command::new("systemctl")
+        .args(args)
+        .status()
+        .map_err(|e| format!("Failed to run systemctl {}: {}", args.join(" "), e))?;
+    if !status.success() {
+        return Err(format!(
+            "systemctl {} exited with {}",
+            args.join(" "),
+            status
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,228 @@
+use crate::{
+    config::{self, Config},
+    platform_profile, service,
+    thresholds::{self, ThresholdKind, Thresholds},
+};
+use std::{
+    fs,
+    io::{self, BufRead, Write},
+    path::{Path, PathBuf},
+};
+
+// Ctrl-D at any prompt leaves everything as it was
+struct Cancelled;
+
+pub fn run(bat_paths: &[PathBuf], config: &Config, min_end: Option<u8>) -> Result<(), String> {
+    println!("batty setup: press Enter to keep the value in [brackets], Ctrl-D to cancel.");
+    println!();
+
+    match ask_all(bat_paths, config, min_end) {
+        Ok(answers) => apply(answers, min_end, config.platform_profile.as_deref()),
+        Err(Cancelled) => {
+            println!();
+            println!("Setup cancelled; nothing was changed");
+            Ok(())
+        }
+    }
+}
+
+struct Answers {
+    battery: PathBuf,
+    start: u8,
+    end: u8,
+    apply_now: bool,
+    install_service: bool,
+}
+
+fn ask_all(
+    bat_paths: &[PathBuf],
+    config: &Config,
+    min_end: Option<u8>,
+) -> Result<Answers, Cancelled> {
+    let names: Vec<String> = bat_paths
+        .iter()
+        .filter_map(|path| path.file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .collect();
+
+    // The first path is already the preferred battery, if one is configured
+    let battery = if bat_paths.len() > 1 {
+        let question = format!("Battery to manage ({})", names.join(", "));
+        let name = ask_until(&question, &names[0], |answer| {
+            names
+                .iter()
+                .position(|name| name.eq_ignore_ascii_case(answer))
+                .ok_or_else(|| format!("choose one of {}", names.join(", ")))
+        })?;
+        bat_paths[name].clone()
+    } else {
+        bat_paths[0].clone()
+    };
+
+    // Re-running offers the saved values, else what the hardware has now
+    let current = Thresholds::load(&battery).ok();
+    let (default_start, default_end) = config
+        .thresholds
+        .map(|t| (t.start, t.end))
+        .or(current.map(|t| (t.start, t.end)))
+        .unwrap_or(thresholds::LONGEVITY_PRESET);
+
+    let end = ask_until("End threshold (%)", &default_end.to_string(), |answer| {
+        let end = parse_percent(answer)?;
+        match min_end {
+            Some(min) if end < min => {
+                Err(format!("{}% is below the safety minimum of {}%", end, min))
+            }
+            _ => Ok(end),
+        }
+    })?;
+
+    let asks_start = thresholds::manages_start()
+        && thresholds::get_path_for_kind(&battery, &ThresholdKind::Start).exists();
+    let start = if asks_start {
+        let default_start = default_start.min(end.saturating_sub(1));
+        ask_until(
+            "Start threshold (%)",
+            &default_start.to_string(),
+            |answer| {
+                let start = parse_percent(answer)?;
+                if start >= end {
+                    return Err(format!("must be below the end threshold ({}%)", end));
+                }
+                Ok(start)
+            },
+        )?
+    } else {
+        default_start.min(end.saturating_sub(1))
+    };
+
+    let apply_now = ask_yes_no("Apply these thresholds now?", true)?;
+    let install_service = ask_yes_no(
+        "Install a systemd service that re-applies them at boot?",
+        false,
+    )?;
+
+    Ok(Answers {
+        battery,
+        start,
+        end,
+        apply_now,
+        install_service,
+    })
+}
+
+fn apply(
+    answers: Answers,
+    min_end: Option<u8>,
+    platform_profile: Option<&str>,
+) -> Result<(), String> {
+    let path = config::config_path();
+    let battery_name = answers
+        .battery
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    write_config(&path, &battery_name, answers.start, answers.end)?;
+    println!();
+    println!("Wrote {}", path.display());
+
+    if answers.apply_now {
+        let mut thresholds = Thresholds::load(&answers.battery)
+            .map_err(|e| format!("Failed to load current thresholds: {}", e))?;
+        thresholds.set_both(answers.start, answers.end)?;
+        if let Some(min_end) = min_end {
+            thresholds.check_min_end(min_end)?;
+        }
+        if let Some(warning) = thresholds::check_discharge_guard(&answers.battery)? {
+            eprintln!("Warning: {}", warning);
+        }
+        thresholds
+            .save(&answers.battery)
+            .map_err(|e| format!("Failed to save thresholds: {}", e))?;
+        println!(
+            "Applied {}%-{}% to {}",
+            answers.start, answers.end, battery_name
+        );
+        if let Some(profile) = platform_profile {
+            match platform_profile::apply(profile) {
+                Ok(()) => println!("Platform profile set to {}", profile),
+                Err(e) => eprintln!("Warning: {}", e),
+            }
+        }
+    }
+
+    if answers.install_service {
+        service::install(&path)?;
+    }
+    Ok(())
+}
+
+// Other keys (and the rest of the file's values) are kept; comments are not
+fn write_config(path: &Path, battery: &str, start: u8, end: u8) -> Result<(), String> {
+    let mut table = match fs::read_to_string(path) {
+        Ok(contents) => contents
+            .parse::<toml::Table>()
+            .map_err(|e| format!("Invalid config {}: {}", path.display(), e))?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => toml::Table::new(),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+
+    table.insert("battery".to_string(), battery.into());
+    let mut thresholds = toml::Table::new();
+    thresholds.insert("start".to_string(), i64::from(start).into());
+    thresholds.insert("end".to_string(), i64::from(end).into());
+    table.insert("thresholds".to_string(), thresholds.into());
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    fs::write(path, table.to_string())
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn parse_percent(answer: &str) -> Result<u8, String> {
+    match answer.trim_end_matches('%').parse::<u8>() {
+        Ok(value) if value <= 100 => Ok(value),
+        _ => Err("enter a number between 0 and 100".to_string()),
+    }
+}
+
+// Repeats the question until `parse` accepts the answer (or the default)
+fn ask_until<T>(
+    question: &str,
+    default: &str,
+    parse: impl Fn(&str) -> Result<T, String>,
+) -> Result<T, Cancelled> {
+    loop {
+        let answer = prompt(&format!("{} [{}]: ", question, default))?;
+        let answer = if answer.is_empty() { default } else { &answer };
+        match parse(answer) {
+            Ok(value) => return Ok(value),
+            Err(e) => println!("  {}", e),
+        }
+    }
+}
+
+fn ask_yes_no(question: &str, default: bool) -> Result<bool, Cancelled> {
+    let hint = if default { "Y/n" } else { "y/N" };
+    loop {
+        let answer = prompt(&format!("{} [{}]: ", question, hint))?;
+        match answer.to_lowercase().as_str() {
+            "" => return Ok(default),
+            "y" | "yes" => return Ok(true),
+            "n" | "no" => return Ok(false),
+            _ => println!("  answer y or n"),
+        }
+    }
+}
+
+fn prompt(text: &str) -> Result<String, Cancelled> {
+    print!("{}", text);
+    let _ = io::stdout().flush();
+    let mut line = String::new();
+    match io::stdin().lock().read_line(&mut line) {
+        Ok(0) | Err(_) => Err(Cancelled),
+        Ok(_) => Ok(line.trim().to_string()),
+    }
+}
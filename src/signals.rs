@@ -0,0 +1,45 @@
+use signal_hook::consts::{SIGINT, SIGTERM};
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, OnceLock,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+// How often long sleeps wake up to check for a pending signal
+const POLL_SLICE: Duration = Duration::from_millis(100);
+
+static TERMINATE: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+
+// Replaces the default "die immediately" behavior of SIGTERM/SIGINT with a
+// flag that long-running loops check so they can clean up and exit
+pub fn install() -> io::Result<()> {
+    let flag = TERMINATE.get_or_init(|| Arc::new(AtomicBool::new(false)));
+    signal_hook::flag::register(SIGTERM, Arc::clone(flag))?;
+    signal_hook::flag::register(SIGINT, Arc::clone(flag))?;
+    Ok(())
+}
+
+pub fn terminate_requested() -> bool {
+    TERMINATE
+        .get()
+        .is_some_and(|flag| flag.load(Ordering::Relaxed))
+}
+
+// Returns false if a termination signal arrived before the duration elapsed
+pub fn sleep(duration: Duration) -> bool {
+    let deadline = Instant::now() + duration;
+    loop {
+        if terminate_requested() {
+            return false;
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return true;
+        }
+        thread::sleep(remaining.min(POLL_SLICE));
+    }
+}
@@ -0,0 +1,73 @@
+use std::{
+    cell::RefCell,
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+thread_local! {
+    // Trees alive on this test's thread, newest last
+    static ROOTS: RefCell<Vec<PathBuf>> = const { RefCell::new(Vec::new()) };
+}
+
+// The newest live tree on this thread. The lock file and state directory live
+// under it in tests, so they never touch /run or the user's home.
+pub fn current_root() -> Option<PathBuf> {
+    ROOTS.with(|roots| roots.borrow().last().cloned())
+}
+
+// A throwaway power_supply directory for the unit tests, removed on drop.
+// Each tree gets its own directory, so tests can run in parallel.
+pub struct MockTree {
+    root: PathBuf,
+}
+
+impl MockTree {
+    pub fn new() -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let root = env::temp_dir().join(format!(
+            "batty-test-{}-{}",
+            process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&root).expect("failed to create the mock tree");
+        ROOTS.with(|roots| roots.borrow_mut().push(root.clone()));
+        Self { root }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.root
+    }
+
+    // A battery directory holding `type` = Battery plus the given attributes
+    pub fn battery(&self, name: &str, attributes: &[(&str, &str)]) -> PathBuf {
+        let path = self.root.join(name);
+        self.write(&path, "type", "Battery\n");
+        for (file, value) in attributes {
+            self.write(&path, file, format!("{}\n", value));
+        }
+        path
+    }
+
+    // Writes `contents` to `dir/file`, creating subdirectories such as device/
+    pub fn write(&self, dir: &Path, file: &str, contents: impl AsRef<[u8]>) {
+        let path = dir.join(file);
+        fs::create_dir_all(path.parent().unwrap()).expect("failed to create the mock directory");
+        fs::write(&path, contents).expect("failed to write the mock attribute");
+    }
+
+    pub fn read(&self, dir: &Path, file: &str) -> String {
+        fs::read_to_string(dir.join(file))
+            .expect("failed to read the mock attribute")
+            .trim()
+            .to_string()
+    }
+}
+
+impl Drop for MockTree {
+    fn drop(&mut self) {
+        ROOTS.with(|roots| roots.borrow_mut().retain(|root| *root != self.root));
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
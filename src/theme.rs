@@ -0,0 +1,86 @@
+use crate::color;
+use clap::ValueEnum;
+use ratatui::style::{Color, Modifier, Style};
+use serde::Deserialize;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeName {
+    // Named ANSI colors, so the terminal's own palette decides the shades
+    #[default]
+    Dark,
+    // Darker 256-color shades that stay readable on a white background
+    Light,
+    // Bold green/yellow/red levels, plain foreground text instead of gray, reversed highlights
+    HighContrast,
+}
+
+// Every color the TUI draws with. Styles rather than colors, so a theme can
+// also add modifiers such as bold.
+#[derive(Clone, Copy)]
+pub struct Theme {
+    // Charge at or above 50%, status messages, the health chart
+    pub good: Style,
+    // Charge below 50%, warnings, the read-only notice
+    pub warning: Style,
+    // Charge below 20%, errors
+    pub critical: Style,
+    // Labels, notes, empty bays and batteries of unknown charge
+    pub dim: Style,
+    // The cycles chart
+    pub accent: Style,
+    // The highlighted row in the battery picker
+    pub highlight: Style,
+    // The "charged" pulse in the status box
+    pub pulse: Style,
+}
+
+impl Theme {
+    pub fn new(name: ThemeName) -> Self {
+        let fg = |color| Style::default().fg(color);
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        match name {
+            ThemeName::Dark => Self {
+                good: fg(Color::Green),
+                warning: fg(Color::Yellow),
+                critical: fg(Color::Red),
+                dim: fg(Color::DarkGray),
+                accent: fg(Color::Cyan),
+                highlight: fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                pulse: fg(Color::Black)
+                    .bg(Color::Green)
+                    .add_modifier(Modifier::BOLD),
+            },
+            ThemeName::Light => Self {
+                good: fg(Color::Indexed(28)),
+                warning: fg(Color::Indexed(130)),
+                critical: fg(Color::Indexed(160)),
+                dim: fg(Color::Indexed(242)),
+                accent: fg(Color::Indexed(25)),
+                highlight: fg(Color::Indexed(25)).add_modifier(Modifier::BOLD),
+                pulse: fg(Color::White)
+                    .bg(Color::Indexed(28))
+                    .add_modifier(Modifier::BOLD),
+            },
+            ThemeName::HighContrast => Self {
+                good: bold.fg(Color::Green),
+                warning: bold.fg(Color::Yellow),
+                critical: bold.fg(Color::Red),
+                dim: Style::default(),
+                accent: bold,
+                highlight: bold.add_modifier(Modifier::REVERSED),
+                pulse: bold.add_modifier(Modifier::REVERSED),
+            },
+        }
+    }
+
+    // Maps the colored CLI output's levels, so both agree on what's low
+    pub fn level(&self, level: color::Color) -> Style {
+        match level {
+            color::Color::Red => self.critical,
+            color::Color::Yellow => self.warning,
+            color::Color::Green => self.good,
+            color::Color::Dim => self.dim,
+        }
+    }
+}
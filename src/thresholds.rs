@@ -1,11 +1,85 @@
+use crate::{ac, battery, error::BattyError, lock::ThresholdLock};
+use serde::Deserialize;
 use std::{
-    fmt,
-    fs,
-    io,
+    env, fmt, fs,
+    io::{self, Write},
     path::{Path, PathBuf},
+    process::Command,
+    str::FromStr,
+    sync::OnceLock,
 };
 
-#[derive(PartialEq, Clone, Copy)]
+// Lowest end threshold accepted without --force; below this the battery barely charges
+pub const DEFAULT_MIN_END: u8 = 20;
+
+// Cleared by manage_start = false / --end-only for firmware that misbehaves
+// when the start threshold is written
+static MANAGE_START: OnceLock<bool> = OnceLock::new();
+
+pub fn set_manage_start(manage: bool) {
+    let _ = MANAGE_START.set(manage);
+}
+
+pub fn manages_start() -> bool {
+    MANAGE_START.get().copied().unwrap_or(true)
+}
+
+// Some firmware mishandles threshold writes while running on battery, and the
+// new values often only take effect after the next replug anyway
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DischargeGuard {
+    Off,
+    #[default]
+    Warn,
+    // Refuse the write unless --force is given
+    Block,
+}
+
+static DISCHARGE_GUARD: OnceLock<DischargeGuard> = OnceLock::new();
+
+pub fn set_discharge_guard(guard: DischargeGuard) {
+    let _ = DISCHARGE_GUARD.set(guard);
+}
+
+// Ok(Some(warning)) means the write may go ahead but the user should know
+pub fn check_discharge_guard(base_path: &Path) -> Result<Option<String>, String> {
+    let guard = DISCHARGE_GUARD.get().copied().unwrap_or_default();
+    if guard == DischargeGuard::Off || !on_battery_power(base_path) {
+        return Ok(None);
+    }
+    let name = base_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let message = format!(
+        "{} is discharging with AC disconnected; the new thresholds may only take effect once the charger is plugged in",
+        name
+    );
+    match guard {
+        DischargeGuard::Block => Err(format!(
+            "{} (discharge_guard = \"block\"; plug in AC or pass --force)",
+            message
+        )),
+        _ => Ok(Some(message)),
+    }
+}
+
+fn on_battery_power(base_path: &Path) -> bool {
+    let discharging = fs::read_to_string(base_path.join("status"))
+        .map(|status| status.trim().eq_ignore_ascii_case("discharging"))
+        .unwrap_or(false);
+    discharging
+        && base_path
+            .parent()
+            .is_some_and(|dir| ac::active_source(&ac::find_sources(dir)).is_none())
+}
+
+// (start, end) pairs for the --longevity and --maximize-capacity presets
+pub const LONGEVITY_PRESET: (u8, u8) = (40, 80);
+pub const MAXIMIZE_CAPACITY_PRESET: (u8, u8) = (0, 100);
+
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum ThresholdKind {
     Start,
     End,
@@ -20,36 +94,147 @@ impl fmt::Display for ThresholdKind {
     }
 }
 
+#[derive(Debug)]
+pub struct ParseThresholdKindError(String);
+
+impl fmt::Display for ParseThresholdKindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid kind '{}', expected 'start' or 'end'", self.0)
+    }
+}
+
+impl std::error::Error for ParseThresholdKindError {}
+
+impl FromStr for ThresholdKind {
+    type Err = ParseThresholdKindError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "start" => Ok(ThresholdKind::Start),
+            "end" => Ok(ThresholdKind::End),
+            _ => Err(ParseThresholdKindError(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum WriteError {
+    // The write itself failed (permissions, I/O, value rejected by the driver)
+    Attribute(BattyError),
+    // The old start value couldn't be read, so there is nothing to roll back to
+    PreviousUnreadable(BattyError),
+    // The write succeeded but the file reads back a different value
+    NotApplied {
+        path: PathBuf,
+        expected: u8,
+        actual: u8,
+    },
+    // The start threshold was written but the end write failed afterwards.
+    // `rollback` holds the restored start value or why it couldn't be restored.
+    PartiallyApplied {
+        error: Box<WriteError>,
+        start: u8,
+        rollback: Result<u8, Box<WriteError>>,
+    },
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WriteError::Attribute(e) => write!(f, "{}", e),
+            WriteError::PreviousUnreadable(e) => {
+                write!(f, "the previous value was unreadable: {}", e)
+            }
+            WriteError::NotApplied {
+                path,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{} reads back {}% after writing {}%; the firmware rejected or adjusted the value",
+                path.display(),
+                actual,
+                expected
+            ),
+            WriteError::PartiallyApplied {
+                error,
+                rollback: Ok(previous),
+                ..
+            } => write!(
+                f,
+                "end threshold: {}; the start threshold was rolled back to {}%",
+                error, previous
+            ),
+            WriteError::PartiallyApplied {
+                error,
+                start,
+                rollback: Err(rollback_error),
+            } => write!(
+                f,
+                "end threshold: {}; the start threshold is now {}% and could not be rolled back: {}",
+                error, start, rollback_error
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WriteError {}
+
+impl From<BattyError> for WriteError {
+    fn from(e: BattyError) -> Self {
+        WriteError::Attribute(e)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
 pub struct Thresholds {
     pub start: u8,
     pub end: u8,
 }
 
 impl Thresholds {
-    pub fn load(base_path: &Path) -> io::Result<Self> {
-        let start_path = get_path_for_kind(base_path, &ThresholdKind::Start);
-        let end_path = get_path_for_kind(base_path, &ThresholdKind::End);
+    pub fn load(base_path: &Path) -> Result<Self, BattyError> {
+        let start_file = resolve(base_path, &ThresholdKind::Start);
+        let end_file = resolve(base_path, &ThresholdKind::End);
 
-        let start = match read_threshold(&start_path) {
+        let start = match read_threshold(base_path, &start_file) {
             Ok(value) => value,
-            Err(err) if err.kind() == io::ErrorKind::NotFound => 0,
+            Err(err) if err.is_missing() => 0,
             Err(err) => return Err(err),
         };
-        let end = read_threshold(&end_path)?;
+        let end = read_threshold(base_path, &end_file)?;
 
         Ok(Self { start, end })
     }
 
-    pub fn save(&self, base_path: &Path) -> io::Result<()> {
-        let start_path = get_path_for_kind(base_path, &ThresholdKind::Start);
-        let end_path = get_path_for_kind(base_path, &ThresholdKind::End);
+    pub fn save(&self, base_path: &Path) -> Result<(), WriteError> {
+        let _lock = ThresholdLock::acquire().map_err(BattyError::Lock)?;
+        let start_file = resolve(base_path, &ThresholdKind::Start);
+        let end_file = resolve(base_path, &ThresholdKind::End);
 
-        if start_path.exists() {
-            write_threshold(&start_path, self.start)?;
+        if !(manages_start() && start_file.path.exists()) {
+            return write_threshold(base_path, &end_file, self.end);
         }
-        write_threshold(&end_path, self.end)?;
 
-        Ok(())
+        // Remember the old start so a failed end write doesn't leave the pair
+        // half-applied
+        let previous_start = read_threshold(base_path, &start_file);
+        write_threshold(base_path, &start_file, self.start)?;
+        let Err(error) = write_threshold(base_path, &end_file, self.end) else {
+            return Ok(());
+        };
+
+        let rollback = match previous_start {
+            Ok(previous) => write_threshold(base_path, &start_file, previous)
+                .map(|()| previous)
+                .map_err(Box::new),
+            Err(e) => Err(Box::new(WriteError::PreviousUnreadable(e))),
+        };
+        Err(WriteError::PartiallyApplied {
+            error: Box::new(error),
+            start: self.start,
+            rollback,
+        })
     }
 
     pub fn get(&self, kind: ThresholdKind) -> u8 {
@@ -66,13 +251,19 @@ impl Thresholds {
 
         match kind {
             ThresholdKind::Start => {
+                if !manages_start() {
+                    return Err(
+                        "the start threshold is not managed (manage_start = false)".to_string()
+                    );
+                }
                 if value >= self.end {
                     return Err("start threshold must be less than end threshold".to_string());
                 }
                 self.start = value;
             }
             ThresholdKind::End => {
-                if value <= self.start {
+                // In end-only mode the start is never written, so it can't conflict
+                if manages_start() && value <= self.start {
                     return Err("end threshold must be greater than start threshold".to_string());
                 }
                 self.end = value;
@@ -81,6 +272,48 @@ impl Thresholds {
 
         Ok(())
     }
+
+    pub fn check_min_end(&self, min_end: u8) -> Result<(), String> {
+        if self.end < min_end {
+            return Err(format!(
+                "end threshold {}% is below the safety minimum of {}%",
+                self.end, min_end
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn set_both(&mut self, start: u8, end: u8) -> Result<(), String> {
+        if start > 100 || end > 100 {
+            return Err("threshold must be between 0 and 100".to_string());
+        }
+        // End-only mode keeps the current start and applies just the end
+        if !manages_start() {
+            self.end = end;
+            return Ok(());
+        }
+        if start >= end {
+            return Err("start threshold must be less than end threshold".to_string());
+        }
+
+        self.start = start;
+        self.end = end;
+        Ok(())
+    }
+}
+
+// Start/end pair for a charge window ending at `end`, e.g. 75-80 for a 5% margin
+pub fn window(end: u8, margin: u8) -> Result<(u8, u8), String> {
+    if margin == 0 {
+        return Err("margin must be at least 1 so the start stays below the end".to_string());
+    }
+    let start = end.checked_sub(margin).ok_or_else(|| {
+        format!(
+            "a margin of {}% below {}% would put the start threshold below 0%",
+            margin, end
+        )
+    })?;
+    Ok((start, end))
 }
 
 impl Default for Thresholds {
@@ -89,24 +322,503 @@ impl Default for Thresholds {
     }
 }
 
-pub fn get_path_for_kind(base_path: &Path, kind: &ThresholdKind) -> PathBuf {
+pub fn is_supported(base_path: &Path) -> bool {
+    get_path_for_kind(base_path, &ThresholdKind::End).exists()
+}
+
+// Opening for write checks permissions without touching the value
+pub fn check_writable(base_path: &Path) -> Result<(), BattyError> {
+    let path = get_path_for_kind(base_path, &ThresholdKind::End);
+    fs::OpenOptions::new()
+        .write(true)
+        .open(&path)
+        .map(|_| ())
+        .map_err(|e| BattyError::from_io(&path, e))
+}
+
+// Explains up front that saving will fail, instead of on the first save. A
+// configured write helper may still succeed, so nothing is reported then.
+pub fn write_access_warnings(base_path: &Path) -> Vec<battery::Warning> {
+    if write_helper().is_some() || !is_supported(base_path) {
+        return Vec::new();
+    }
+    let Err(e) = check_writable(base_path) else {
+        return Vec::new();
+    };
+    vec![
+        battery::Warning::warn(format!(
+            "Readings work, but saving thresholds will fail: {}",
+            e
+        )),
+        battery::Warning::info(
+            "Run batty with sudo, or add this rule to /etc/udev/rules.d/99-batty.rules:",
+        ),
+        // On its own line so it can be copied as is
+        battery::Warning::info(udev_rule(base_path)),
+    ]
+}
+
+fn udev_rule(base_path: &Path) -> String {
+    let name = base_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut kinds = vec![ThresholdKind::End];
+    if manages_start() && get_path_for_kind(base_path, &ThresholdKind::Start).exists() {
+        kinds.insert(0, ThresholdKind::Start);
+    }
+    // %p is the device path udev matched, which is where the files live
+    let files: Vec<String> = kinds
+        .iter()
+        .map(|kind| {
+            let path = get_path_for_kind(base_path, kind);
+            match path.strip_prefix(base_path) {
+                Ok(relative) => format!("/sys%p/{}", relative.display()),
+                Err(_) => path.display().to_string(),
+            }
+        })
+        .collect();
+    format!(
+        "ACTION==\"add\", SUBSYSTEM==\"power_supply\", KERNEL==\"{}\", RUN+=\"/bin/chmod a+w {}\"",
+        name,
+        files.join(" ")
+    )
+}
+
+#[derive(Clone, Copy)]
+enum ThresholdUnit {
+    // Plain 0-100 percentage
+    Percent,
+    // Fraction of full charge between 0.0 and 1.0, used by a few drivers
+    Fraction,
+    // Raw charge in the same unit as charge_full_design (µAh)
+    DesignCharge,
+}
+
+impl ThresholdUnit {
+    // `path` is the threshold file `raw` was read from, for error messages
+    fn normalize(self, base_path: &Path, path: &Path, raw: &str) -> Result<u8, BattyError> {
+        let invalid = || BattyError::unparseable(path, raw);
+        match self {
+            Self::Percent => raw.parse::<u8>().map_err(|_| invalid()),
+            Self::Fraction => parse_fraction(raw).ok_or_else(invalid),
+            Self::DesignCharge => {
+                let value: u64 = raw.parse().map_err(|_| invalid())?;
+                let design = read_design_charge(base_path)?;
+                Ok(((value * 100 + design / 2) / design).min(100) as u8)
+            }
+        }
+    }
+
+    fn denormalize(self, base_path: &Path, percent: u8) -> Result<String, BattyError> {
+        match self {
+            Self::Percent => Ok(percent.to_string()),
+            Self::Fraction => Ok(format!("{:.2}", f32::from(percent) / 100.0)),
+            Self::DesignCharge => {
+                let design = read_design_charge(base_path)?;
+                Ok((design * u64::from(percent) / 100).to_string())
+            }
+        }
+    }
+}
+
+struct ThresholdFile {
+    path: PathBuf,
+    unit: ThresholdUnit,
+}
+
+// Generic charge_control_* names first (ASUS and most newer drivers), then
+// the names older thinkpad_acpi versions use. Files that don't hold a plain
+// percentage carry their unit so reads and writes can be converted.
+fn candidates_for_kind(kind: &ThresholdKind) -> &'static [(&'static str, ThresholdUnit)] {
     match kind {
-        ThresholdKind::Start => base_path.join("charge_control_start_threshold"),
-        ThresholdKind::End => base_path.join("charge_control_end_threshold"),
+        ThresholdKind::Start => &[
+            ("charge_control_start_threshold", ThresholdUnit::Percent),
+            ("charge_start_threshold", ThresholdUnit::Percent),
+        ],
+        ThresholdKind::End => &[
+            ("charge_control_end_threshold", ThresholdUnit::Percent),
+            ("charge_stop_threshold", ThresholdUnit::Percent),
+            ("charge_limit", ThresholdUnit::DesignCharge),
+        ],
     }
 }
 
-fn read_threshold(path: &Path) -> io::Result<u8> {
-    let current = fs::read_to_string(path)?;
-    let trimmed = current.trim();
-    trimmed.parse::<u8>().map_err(|_| {
-        io::Error::new(
-            io::ErrorKind::InvalidData,
-            format!("invalid threshold value: {}", trimmed),
-        )
-    })
+// Some platforms (e.g. ASUS WMI on a few models) put the controls one level
+// deeper, in the battery's device directory
+const THRESHOLD_SUBDIRS: &[&str] = &["", "device"];
+
+fn resolve(base_path: &Path, kind: &ThresholdKind) -> ThresholdFile {
+    let candidates = candidates_for_kind(kind);
+    let (path, unit) = THRESHOLD_SUBDIRS
+        .iter()
+        .flat_map(|subdir| {
+            candidates
+                .iter()
+                .map(move |(name, unit)| (base_path.join(subdir).join(name), unit))
+        })
+        .find(|(path, _)| path.exists())
+        .unwrap_or_else(|| (base_path.join(candidates[0].0), &candidates[0].1));
+    let unit = match unit {
+        ThresholdUnit::Percent if holds_fraction(&path) => ThresholdUnit::Fraction,
+        unit => *unit,
+    };
+
+    ThresholdFile { path, unit }
+}
+
+// Only treat a percentage file as fractional when it doesn't parse as an
+// integer, so "1" keeps meaning 1% rather than 100%
+fn holds_fraction(path: &Path) -> bool {
+    fs::read_to_string(path)
+        .map(|raw| {
+            let raw = raw.trim();
+            raw.parse::<u8>().is_err() && parse_fraction(raw).is_some()
+        })
+        .unwrap_or(false)
+}
+
+fn parse_fraction(raw: &str) -> Option<u8> {
+    raw.parse::<f32>()
+        .ok()
+        .filter(|fraction| (0.0..=1.0).contains(fraction))
+        .map(|fraction| (fraction * 100.0).round() as u8)
+}
+
+pub fn get_path_for_kind(base_path: &Path, kind: &ThresholdKind) -> PathBuf {
+    resolve(base_path, kind).path
+}
+
+// Returns the percentage of energy_full that `wh` represents, plus energy_full in Wh
+pub fn percent_of_full_energy(base_path: &Path, wh: f32) -> Result<(u8, f32), String> {
+    let full = fs::read_to_string(base_path.join("energy_full"))
+        .ok()
+        .and_then(|raw| raw.trim().parse::<u64>().ok())
+        .filter(|&full| full > 0)
+        .ok_or_else(|| {
+            format!(
+                "{} does not report energy_full, so Wh thresholds are unsupported",
+                base_path.display()
+            )
+        })?;
+    let full_wh = full as f32 / battery::units_per_wh(Some(full));
+
+    if wh.is_nan() || wh <= 0.0 {
+        return Err("energy threshold must be greater than 0 Wh".to_string());
+    }
+    if wh > full_wh {
+        return Err(format!(
+            "{:.1} Wh exceeds the battery's full capacity of {:.1} Wh",
+            wh, full_wh
+        ));
+    }
+
+    Ok(((wh / full_wh * 100.0).round() as u8, full_wh))
+}
+
+fn read_design_charge(base_path: &Path) -> Result<u64, BattyError> {
+    let path = base_path.join("charge_full_design");
+    let raw = fs::read_to_string(&path).map_err(|e| BattyError::from_io(&path, e))?;
+    match raw.trim().parse::<u64>() {
+        Ok(design) if design > 0 => Ok(design),
+        _ => Err(BattyError::unparseable(&path, raw.trim())),
+    }
+}
+
+fn read_threshold(base_path: &Path, file: &ThresholdFile) -> Result<u8, BattyError> {
+    let current =
+        battery::read_with_retry(&file.path).map_err(|e| BattyError::from_io(&file.path, e))?;
+    file.unit.normalize(
+        base_path,
+        &file.path,
+        String::from_utf8_lossy(&current).trim(),
+    )
+}
+
+// Reads the value back after writing, since some firmware accepts the write
+// but silently clamps or ignores it
+fn write_threshold(base_path: &Path, file: &ThresholdFile, value: u8) -> Result<(), WriteError> {
+    let raw = file.unit.denormalize(base_path, value)?;
+    write_attribute(&file.path, &raw)?;
+
+    let actual = read_threshold(base_path, file)?;
+    if actual != value {
+        return Err(WriteError::NotApplied {
+            path: file.path.clone(),
+            expected: value,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+// Falls back to BATTY_WRITE_HELPER when the direct write is denied
+pub fn write_attribute(path: &Path, raw: &str) -> Result<(), BattyError> {
+    match write_synced(path, raw) {
+        Err(err) if err.kind() == io::ErrorKind::PermissionDenied => match write_helper() {
+            Some(helper) => write_with_helper(&helper, path, raw),
+            None => Err(BattyError::from_io(path, err)),
+        },
+        result => result.map_err(|e| BattyError::from_io(path, e)),
+    }
+}
+
+// The driver sees the value on write(); fsync is only meaningful for regular
+// files (e.g. a mock tree) and sysfs answers it with EINVAL
+fn write_synced(path: &Path, raw: &str) -> io::Result<()> {
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .truncate(true)
+        .open(path)?;
+    file.write_all(raw.as_bytes())?;
+    file.flush()?;
+    match file.sync_all() {
+        Err(e) if e.kind() == io::ErrorKind::InvalidInput => Ok(()),
+        result => result,
+    }
+}
+
+fn write_helper() -> Option<PathBuf> {
+    env::var_os("BATTY_WRITE_HELPER")
+        .filter(|helper| !helper.is_empty())
+        .map(PathBuf::from)
 }
 
-fn write_threshold(path: &Path, value: u8) -> io::Result<()> {
-    fs::write(path, value.to_string())
+// Delegates the write to a privileged helper invoked as `<helper> <path> <value>`
+fn write_with_helper(helper: &Path, path: &Path, value: &str) -> Result<(), BattyError> {
+    let status = Command::new(helper)
+        .arg(path)
+        .arg(value)
+        .status()
+        .map_err(|e| BattyError::WriteHelper {
+            helper: helper.to_path_buf(),
+            reason: format!("could not be run: {}", e),
+        })?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(BattyError::WriteHelper {
+            helper: helper.to_path_buf(),
+            reason: format!("exited with {}", status),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::MockTree;
+    use std::fs;
+
+    #[test]
+    fn parse_kind() {
+        for (input, kind) in [
+            ("start", ThresholdKind::Start),
+            ("end", ThresholdKind::End),
+            ("Start", ThresholdKind::Start),
+            ("END", ThresholdKind::End),
+            (" end ", ThresholdKind::End),
+        ] {
+            assert_eq!(input.parse::<ThresholdKind>().unwrap(), kind);
+        }
+        for input in ["", "stop", "both", "starts"] {
+            let err = input.parse::<ThresholdKind>().unwrap_err();
+            assert!(err.to_string().contains("expected 'start' or 'end'"));
+        }
+    }
+
+    #[test]
+    fn thresholds_in_battery_directory() {
+        let tree = MockTree::new();
+        let path = tree.battery("BAT0", &[("charge_control_end_threshold", "80")]);
+        tree.write(&path, "device/charge_control_end_threshold", "60\n");
+        // The battery directory wins when both exist
+        assert_eq!(
+            get_path_for_kind(&path, &ThresholdKind::End),
+            path.join("charge_control_end_threshold")
+        );
+        assert_eq!(Thresholds::load(&path).unwrap().end, 80);
+    }
+
+    #[test]
+    fn thresholds_in_device_directory() {
+        let tree = MockTree::new();
+        let path = tree.battery("BAT0", &[]);
+        tree.write(&path, "device/charge_control_start_threshold", "40\n");
+        tree.write(&path, "device/charge_control_end_threshold", "80\n");
+        assert!(is_supported(&path));
+        assert_eq!(
+            get_path_for_kind(&path, &ThresholdKind::Start),
+            path.join("device/charge_control_start_threshold")
+        );
+
+        Thresholds { start: 50, end: 90 }.save(&path).unwrap();
+        assert_eq!(
+            tree.read(&path, "device/charge_control_start_threshold"),
+            "50"
+        );
+        assert_eq!(
+            tree.read(&path, "device/charge_control_end_threshold"),
+            "90"
+        );
+
+        // The write lock is taken inside the mock tree rather than in /run
+        assert!(tree.path().join("batty.lock").exists());
+    }
+
+    #[test]
+    fn integer_thresholds() {
+        let tree = MockTree::new();
+        // "1" parses as an integer, so it means 1% rather than 100%
+        let path = tree.battery(
+            "BAT0",
+            &[
+                ("charge_control_start_threshold", "1"),
+                ("charge_control_end_threshold", "80"),
+            ],
+        );
+        let thresholds = Thresholds::load(&path).unwrap();
+        assert_eq!((thresholds.start, thresholds.end), (1, 80));
+    }
+
+    #[test]
+    fn fractional_thresholds() {
+        let tree = MockTree::new();
+        let path = tree.battery(
+            "BAT0",
+            &[
+                ("charge_control_start_threshold", "0.4"),
+                ("charge_control_end_threshold", "0.80"),
+            ],
+        );
+        let thresholds = Thresholds::load(&path).unwrap();
+        assert_eq!((thresholds.start, thresholds.end), (40, 80));
+
+        Thresholds { start: 55, end: 75 }.save(&path).unwrap();
+        assert_eq!(tree.read(&path, "charge_control_start_threshold"), "0.55");
+        assert_eq!(tree.read(&path, "charge_control_end_threshold"), "0.75");
+    }
+
+    #[test]
+    fn fraction_range() {
+        assert_eq!(parse_fraction("0.0"), Some(0));
+        assert_eq!(parse_fraction("1.0"), Some(100));
+        assert_eq!(parse_fraction("0.8"), Some(80));
+        assert_eq!(parse_fraction("1.5"), None);
+        assert_eq!(parse_fraction("-0.2"), None);
+        assert_eq!(parse_fraction("abc"), None);
+    }
+
+    #[test]
+    fn failed_end_write_rolls_back_start() {
+        let tree = MockTree::new();
+        let path = tree.battery("BAT0", &[("charge_control_start_threshold", "40")]);
+        // Writing to a directory fails even as root
+        fs::create_dir(path.join("charge_control_end_threshold")).unwrap();
+
+        let err = Thresholds { start: 60, end: 90 }.save(&path).unwrap_err();
+        match err {
+            WriteError::PartiallyApplied {
+                error,
+                start,
+                rollback,
+            } => {
+                assert!(matches!(*error, WriteError::Attribute(_)));
+                assert_eq!(start, 60);
+                assert_eq!(rollback.unwrap(), 40);
+            }
+            other => panic!("expected PartiallyApplied, got {:?}", other),
+        }
+        assert_eq!(tree.read(&path, "charge_control_start_threshold"), "40");
+    }
+
+    #[test]
+    fn failed_end_write_with_unreadable_start() {
+        let tree = MockTree::new();
+        let path = tree.battery("BAT0", &[("charge_control_start_threshold", "garbage")]);
+        fs::create_dir(path.join("charge_control_end_threshold")).unwrap();
+
+        let err = Thresholds { start: 60, end: 90 }.save(&path).unwrap_err();
+        let WriteError::PartiallyApplied {
+            start, rollback, ..
+        } = err
+        else {
+            panic!("expected PartiallyApplied, got {:?}", err);
+        };
+        assert_eq!(start, 60);
+        assert!(matches!(
+            rollback.map_err(|e| *e),
+            Err(WriteError::PreviousUnreadable(_))
+        ));
+        // Nothing to restore, so the new start stays and the error says so
+        assert_eq!(tree.read(&path, "charge_control_start_threshold"), "60");
+    }
+
+    #[test]
+    fn adjusted_read_back_is_not_applied() {
+        let tree = MockTree::new();
+        // With a design charge of 3 µAh, 80% is written as 2 and reads back as 67%
+        let path = tree.battery(
+            "BAT0",
+            &[("charge_full_design", "3"), ("charge_limit", "3")],
+        );
+
+        let err = Thresholds { start: 0, end: 80 }.save(&path).unwrap_err();
+        match err {
+            WriteError::NotApplied {
+                path: file,
+                expected,
+                actual,
+            } => {
+                assert_eq!(file, path.join("charge_limit"));
+                assert_eq!((expected, actual), (80, 67));
+            }
+            other => panic!("expected NotApplied, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn charge_control_names() {
+        let tree = MockTree::new();
+        let path = tree.battery(
+            "BAT0",
+            &[
+                ("charge_control_start_threshold", "40"),
+                ("charge_control_end_threshold", "80"),
+            ],
+        );
+        assert_eq!(
+            get_path_for_kind(&path, &ThresholdKind::End),
+            path.join("charge_control_end_threshold")
+        );
+        let thresholds = Thresholds::load(&path).unwrap();
+        assert_eq!((thresholds.start, thresholds.end), (40, 80));
+    }
+
+    #[test]
+    fn thinkpad_names() {
+        let tree = MockTree::new();
+        let path = tree.battery(
+            "BAT0",
+            &[
+                ("charge_start_threshold", "75"),
+                ("charge_stop_threshold", "90"),
+            ],
+        );
+        assert_eq!(
+            get_path_for_kind(&path, &ThresholdKind::Start),
+            path.join("charge_start_threshold")
+        );
+        assert_eq!(
+            get_path_for_kind(&path, &ThresholdKind::End),
+            path.join("charge_stop_threshold")
+        );
+        let thresholds = Thresholds::load(&path).unwrap();
+        assert_eq!((thresholds.start, thresholds.end), (75, 90));
+
+        Thresholds { start: 40, end: 80 }.save(&path).unwrap();
+        assert_eq!(tree.read(&path, "charge_start_threshold"), "40");
+        assert_eq!(tree.read(&path, "charge_stop_threshold"), "80");
+    }
 }
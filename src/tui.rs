@@ -1,7 +1,23 @@
 use crate::{
-    battery::Battery,
-    thresholds::{ThresholdKind, Thresholds},
+    ac::{self, PowerSource},
+    alarm,
+    battery::{
+        self, Battery, BatterySnapshot, BatteryStatus, FullDisplay, Rounding, Severity, Warning,
+        WearCutoffs,
+    },
+    charge_type, color,
+    config::Profile,
+    fast_charge,
+    history::{self, HistoryEntry},
+    i18n::{tr, tr_args, Msg},
+    keymap::{Action, KeyMap},
+    last_full, platform_profile,
+    report::{self, BatteryReport},
+    signals,
+    theme::Theme,
+    thresholds::{self, ThresholdKind, Thresholds},
 };
+use arboard::Clipboard;
 use crossterm::{
     event::{self, Event, KeyCode},
     execute,
@@ -9,20 +25,82 @@ use crossterm::{
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Alignment, Constraint, Direction, Flex, Layout},
-    style::{Color, Modifier, Style},
+    layout::{Alignment, Constraint, Direction, Flex, Layout, Rect},
+    style::{Modifier, Style},
+    symbols::Marker,
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Tabs},
+    widgets::{
+        block::{Position, Title},
+        Axis, Block, Borders, Chart, Clear, Dataset, GraphType, List, ListItem, ListState,
+        Paragraph, Tabs,
+    },
     Frame, Terminal,
 };
-use std::{io, path::PathBuf, time::Duration};
+use std::{
+    cmp::Ordering,
+    collections::VecDeque,
+    fs,
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
 
 type BattyBackend = CrosstermBackend<io::Stdout>;
 type BattyTerminal = Terminal<BattyBackend>;
 
-pub fn run_tui(bat_paths: Vec<PathBuf>) -> io::Result<()> {
+// Number of drawn frames the "charged" pulse stays visible. While it runs a
+// frame is drawn on every poll tick (250ms), whatever the refresh interval.
+const FULL_PULSE_FRAMES: u8 = 8;
+const TAB_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(5);
+// Beyond this many batteries the tab bar overflows, so a list picker takes over
+const MAX_TABS: usize = 4;
+// Visible footer lines; the rest scroll with PageUp/PageDown
+const FOOTER_LINES: usize = 3;
+// Percentage points from a threshold at which adaptive refresh speeds up
+const NEAR_THRESHOLD_MARGIN: f32 = 2.0;
+// The %/hr rate is fitted over this much recent history, and only shown once
+// the samples span at least RATE_MIN_SPAN
+const RATE_WINDOW: Duration = Duration::from_secs(10 * 60);
+const RATE_MIN_SPAN: Duration = Duration::from_secs(60);
+
+pub struct TuiOptions {
+    pub animations: bool,
+    pub history: bool,
+    pub min_end: Option<u8>,
+    pub rounding: Rounding,
+    pub full_display: FullDisplay,
+    pub keymap: KeyMap,
+    // Problems found while loading the config, shown in the footer
+    pub config_warnings: Vec<Warning>,
+    pub read_only: bool,
+    pub compare: bool,
+    pub power_smoothing: f32,
+    // Number of readings averaged for the displayed wattage
+    pub power_samples: usize,
+    pub refresh_interval: Duration,
+    // (fast, idle) intervals used instead of refresh_interval when the
+    // battery is charging or near a threshold, or idle; None keeps it fixed
+    pub adaptive_refresh: Option<(Duration, Duration)>,
+    pub health_warning: u8,
+    // Loaded with the digit keys 1-9
+    pub profiles: Vec<Profile>,
+    pub platform_profile: Option<String>,
+    pub wear: WearCutoffs,
+    // Start with the warnings/status footer hidden (--no-footer)
+    pub hide_footer: bool,
+    pub theme: Theme,
+}
+
+pub fn run_tui(bat_paths: Vec<PathBuf>, options: TuiOptions) -> io::Result<()> {
+    // Raw mode fails with an obscure error when piped or run without a terminal
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return Err(io::Error::other(
+            "the TUI requires an interactive terminal; use --json or --percent for non-interactive output",
+        ));
+    }
+
     let mut terminal = setup_terminal()?;
-    let result = run_app(&mut terminal, bat_paths);
+    let result = run_app(&mut terminal, bat_paths, options);
     restore_terminal(&mut terminal)?;
     result
 }
@@ -42,29 +120,78 @@ fn restore_terminal(terminal: &mut BattyTerminal) -> io::Result<()> {
     Ok(())
 }
 
-fn run_app(terminal: &mut BattyTerminal, bat_paths: Vec<PathBuf>) -> io::Result<()> {
-    let mut app = App::new(bat_paths)?;
+fn run_app(
+    terminal: &mut BattyTerminal,
+    bat_paths: Vec<PathBuf>,
+    options: TuiOptions,
+) -> io::Result<()> {
+    let mut app = App::new(bat_paths, options)?;
 
     loop {
-        terminal.draw(|frame| draw_ui(frame, &mut app))?;
+        // SIGTERM ends the loop like 'q' so the terminal is still restored
+        if signals::terminate_requested() {
+            return Ok(());
+        }
+
+        app.refresh_if_due();
+        if app.bat_paths.len() > 1 {
+            app.refresh_tab_labels();
+        }
+
+        // Skip the frame when nothing on screen could have changed
+        if app.dirty {
+            terminal.draw(|frame| draw_ui(frame, &mut app))?;
+            app.dirty = false;
+            app.advance_pulse();
+        }
 
         if event::poll(Duration::from_millis(250))? {
-            if let Event::Key(key) = event::read()? {
-                match key.code {
-                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
-                    KeyCode::Up | KeyCode::Char('+') => app.increment(),
-                    KeyCode::Down | KeyCode::Char('-') => app.decrement(),
-                    KeyCode::Enter => app.save(),
-                    KeyCode::Char('j') | KeyCode::Char('k') => app.select_next_threshold_kind(),
-                    KeyCode::Left | KeyCode::Char('[') => app.prev_tab(),
-                    KeyCode::Right | KeyCode::Char(']') => app.next_tab(),
-                    _ => {}
-                }
+            match event::read()? {
+                Event::Key(key) if !app.handle_key(key.code) => return Ok(()),
+                Event::Resize(..) => app.dirty = true,
+                _ => {}
             }
         }
     }
 }
 
+// A tab title: the battery name plus its state, colored by charge level so a
+// low battery stands out even when its tab isn't selected
+#[derive(Clone, PartialEq)]
+struct TabLabel {
+    name: String,
+    state: String,
+    // Resolved against the theme when drawn
+    level: color::Color,
+}
+
+impl TabLabel {
+    fn read(path: &Path) -> Self {
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(tr(Msg::Unknown));
+        let kind = if battery::is_ups(path) { " (UPS)" } else { "" };
+        let snapshot = BatterySnapshot::read(path);
+        let level = match snapshot.capacity {
+            Some(capacity) if snapshot.present => color::Color::for_percentage(capacity as f32),
+            _ => color::Color::Dim,
+        };
+        Self {
+            name: format!("{}{}", name, kind),
+            state: snapshot.label(),
+            level,
+        }
+    }
+
+    fn line(&self, theme: &Theme) -> Line<'static> {
+        Line::from(vec![
+            Span::raw(format!("{} ", self.name)),
+            Span::styled(self.state.clone(), theme.level(self.level)),
+        ])
+    }
+}
+
 struct App {
     battery: Battery,
     bat_paths: Vec<PathBuf>,
@@ -72,30 +199,262 @@ struct App {
     selected_tab: usize,
     curr_threshold_kind: ThresholdKind,
     thresholds: Thresholds,
+    // End threshold currently applied by the hardware (None when unsupported)
+    active_end: Option<u8>,
+    // Low-capacity alarm level, if the battery exposes one
+    alarm: Option<u8>,
+    // Charging mode (charge_type), if the driver exposes one
+    charge_type: Option<String>,
+    // Fast-charge switch state, if the firmware offers one
+    fast_charge: Option<bool>,
     status: Option<String>,
     error: Option<String>,
-    warnings: Vec<String>,
+    warnings: Vec<Warning>,
+    options: TuiOptions,
+    was_full: bool,
+    // Unix time the battery last reached Full, persisted across runs
+    last_full: Option<u64>,
+    full_pulse_frames: u8,
+    tab_labels: Vec<TabLabel>,
+    tab_labels_read_at: Option<Instant>,
+    last_percentage: Option<f32>,
+    trend: Option<Ordering>,
+    // Timestamped charge readings from the last RATE_WINDOW, for the %/hr
+    // rate; cleared together with smoothed_power
+    percent_samples: VecDeque<(Instant, f32)>,
+    show_history: bool,
+    history: Vec<HistoryEntry>,
+    // Raw sysfs attributes, re-read on every refresh while the pane is open
+    show_details: bool,
+    details: Vec<(String, String)>,
+    details_scroll: usize,
+    // Highlighted row while the battery picker is open
+    picker: Option<usize>,
+    show_footer: bool,
+    footer_scroll: usize,
+    // Side-by-side view of every battery, refreshed while it is shown
+    compare: bool,
+    compared: Vec<(Battery, Option<Thresholds>)>,
+    // Exponential moving average of the power draw in W, reset when the
+    // battery switches between charging and discharging
+    smoothed_power: Option<f32>,
+    smoothed_status: BatteryStatus,
+    // Shown once at startup when saving can't work; reads are unaffected
+    write_warnings: Vec<Warning>,
+    // Last few power readings in W, averaged for display; cleared together
+    // with smoothed_power
+    power_samples: VecDeque<f32>,
+    power_sources: Vec<PowerSource>,
+    last_refresh: Option<Instant>,
+    // Kept open because on X11 the copied text is served by this process
+    clipboard: Option<Clipboard>,
+    // Set by key presses, resizes and refreshes that change what's shown; the
+    // next loop iteration redraws and clears it
+    dirty: bool,
 }
 
 impl App {
-    fn new(bat_paths: Vec<PathBuf>) -> io::Result<Self> {
+    fn new(bat_paths: Vec<PathBuf>, options: TuiOptions) -> io::Result<Self> {
         let initial_path = bat_paths[0].clone();
-        let thresholds = Thresholds::load(&initial_path).unwrap_or_default();
+        let loaded = Thresholds::load(&initial_path).ok();
+        let active_end = loaded.as_ref().map(|t| t.end);
+        let thresholds = loaded.unwrap_or_default();
+        let alarm = alarm::read(&initial_path).ok();
+        let charge_type = charge_type::read(&initial_path).ok();
+        let fast_charge = fast_charge::read(&initial_path).ok();
+        let compare = options.compare;
+        let show_footer = !options.hide_footer;
         let (battery, warnings) = Battery::new(&initial_path)?;
+        let was_full = battery.is_full();
+        let last_full = last_full::load(battery.name());
+        let last_percentage = battery.percentage();
+        let write_warnings = if options.read_only {
+            Vec::new()
+        } else {
+            thresholds::write_access_warnings(&initial_path)
+        };
 
         Ok(Self {
             battery,
-            curr_threshold_kind: ThresholdKind::Start,
+            curr_threshold_kind: if thresholds::manages_start() {
+                ThresholdKind::Start
+            } else {
+                ThresholdKind::End
+            },
             base_path: initial_path,
             bat_paths,
             selected_tab: 0,
             thresholds,
+            active_end,
+            alarm,
+            charge_type,
+            fast_charge,
             status: None,
             error: None,
             warnings,
+            options,
+            was_full,
+            last_full,
+            full_pulse_frames: 0,
+            tab_labels: Vec::new(),
+            tab_labels_read_at: None,
+            last_percentage,
+            trend: None,
+            percent_samples: VecDeque::new(),
+            show_history: false,
+            history: Vec::new(),
+            show_details: false,
+            details: Vec::new(),
+            details_scroll: 0,
+            picker: None,
+            show_footer,
+            footer_scroll: 0,
+            compare,
+            compared: Vec::new(),
+            smoothed_power: None,
+            smoothed_status: BatteryStatus::Unknown,
+            power_samples: VecDeque::new(),
+            write_warnings,
+            power_sources: Vec::new(),
+            last_refresh: None,
+            clipboard: None,
+            dirty: true,
         })
     }
 
+    // Applies a key press to the app state; returns false when the app should quit.
+    // Kept free of terminal I/O so key handling can be driven directly.
+    fn handle_key(&mut self, code: KeyCode) -> bool {
+        self.dirty = true;
+        if self.picker.is_some() {
+            match code {
+                KeyCode::Esc => self.close_picker(),
+                KeyCode::Up | KeyCode::Char('k') => self.picker_move(-1),
+                KeyCode::Down | KeyCode::Char('j') => self.picker_move(1),
+                KeyCode::Enter => self.picker_open_selected(),
+                _ => match self.options.keymap.action(code) {
+                    Some(Action::Quit) => return false,
+                    Some(Action::BatteryList) => self.close_picker(),
+                    _ => {}
+                },
+            }
+            return true;
+        }
+
+        match self.options.keymap.action(code) {
+            Some(Action::Quit) => return false,
+            Some(Action::Increment | Action::Decrement | Action::Save)
+                if self.options.read_only =>
+            {
+                self.status = Some(tr(Msg::ReadOnlyRefused).to_string());
+            }
+            Some(Action::Increment | Action::Decrement | Action::Save) if self.battery.ups => {
+                self.status = Some(tr(Msg::UpsNoThresholds).to_string());
+            }
+            Some(Action::Increment) => self.increment(),
+            Some(Action::Decrement) => self.decrement(),
+            Some(Action::Save) => self.save(),
+            Some(Action::SwitchThreshold) => self.select_next_threshold_kind(),
+            Some(Action::PrevTab) => self.prev_tab(),
+            Some(Action::NextTab) => self.next_tab(),
+            Some(Action::ToggleHistory) => self.toggle_history(),
+            Some(Action::BatteryList) => self.open_picker(),
+            Some(Action::Compare) => self.toggle_compare(),
+            Some(Action::ScrollUp) => self.scroll(-1),
+            Some(Action::ScrollDown) => self.scroll(1),
+            Some(Action::CopyStatus) => self.copy_status(),
+            Some(Action::Refresh) => self.force_refresh(),
+            Some(Action::ToggleFooter) => self.show_footer = !self.show_footer,
+            Some(Action::ToggleDetails) => self.toggle_details(),
+            Some(Action::ToggleFastCharge) => self.toggle_fast_charge(),
+            // Unbound digits pick a profile
+            None => {
+                if let KeyCode::Char(digit @ '1'..='9') = code {
+                    self.load_profile(usize::from(digit as u8 - b'1'));
+                }
+            }
+        }
+        true
+    }
+
+    // Only changes the pending values; the user still saves to apply them
+    fn load_profile(&mut self, index: usize) {
+        let Some(profile) = self.options.profiles.get(index) else {
+            return;
+        };
+        if self.options.read_only {
+            self.status = Some(tr(Msg::ReadOnlyRefused).to_string());
+            return;
+        }
+        if self.battery.ups {
+            self.status = Some(tr(Msg::UpsNoThresholds).to_string());
+            return;
+        }
+
+        match self.thresholds.set_both(profile.start, profile.end) {
+            Ok(()) => {
+                self.status = Some(tr_args(
+                    Msg::ProfileLoaded,
+                    &[
+                        &profile.name,
+                        &self.thresholds.start,
+                        &self.thresholds.end,
+                        &self.options.keymap.describe(Action::Save),
+                    ],
+                ));
+                self.error = None;
+            }
+            Err(e) => {
+                self.error = Some(e);
+                self.status = None;
+            }
+        }
+    }
+
+    fn refresh_tab_labels(&mut self) {
+        let stale = self
+            .tab_labels_read_at
+            .is_none_or(|read_at| read_at.elapsed() >= TAB_SNAPSHOT_INTERVAL);
+        if !stale {
+            return;
+        }
+
+        let tab_labels: Vec<TabLabel> = self
+            .bat_paths
+            .iter()
+            .map(|path| TabLabel::read(path))
+            .collect();
+        self.tab_labels_read_at = Some(Instant::now());
+        if tab_labels != self.tab_labels {
+            self.tab_labels = tab_labels;
+            self.dirty = true;
+        }
+    }
+
+    // Called after each drawn frame; keeps frames coming until the pulse ends
+    fn advance_pulse(&mut self) {
+        if self.full_pulse_frames > 0 {
+            self.full_pulse_frames -= 1;
+            self.dirty = true;
+        }
+    }
+
+    fn track_full_transition(&mut self) {
+        let is_full = self.battery.is_full();
+        if is_full && !self.was_full {
+            match last_full::record(self.battery.name()) {
+                Ok(timestamp) => self.last_full = Some(timestamp),
+                Err(e) => self.error = Some(format!("Failed to record last full charge: {}", e)),
+            }
+        }
+        if is_full && !self.was_full && self.options.animations {
+            self.full_pulse_frames = FULL_PULSE_FRAMES;
+        } else if !is_full {
+            self.full_pulse_frames = 0;
+        }
+        self.was_full = is_full;
+    }
+
     fn increment(&mut self) {
         let current = self.thresholds.get(self.curr_threshold_kind);
         let new_val = if current < 100 { current + 1 } else { current };
@@ -127,22 +486,129 @@ impl App {
     }
 
     fn save(&mut self) {
+        if let Some(Err(err)) = self
+            .options
+            .min_end
+            .map(|min| self.thresholds.check_min_end(min))
+        {
+            self.error = Some(err);
+            self.status = None;
+            return;
+        }
+        let guard_warning = match thresholds::check_discharge_guard(&self.base_path) {
+            Ok(warning) => warning,
+            Err(err) => {
+                self.error = Some(err);
+                self.status = None;
+                return;
+            }
+        };
+
         match self.thresholds.save(&self.base_path) {
             Ok(_) => {
+                self.active_end = Some(self.thresholds.end);
                 self.status = Some(format!(
-                    "Battery thresholds set to {}%-{}%",
-                    self.thresholds.start, self.thresholds.end
+                    "{}: {}%-{}%",
+                    tr(Msg::ThresholdsSet),
+                    self.thresholds.start,
+                    self.thresholds.end
                 ));
                 self.error = None;
+                if let Some(warning) = guard_warning {
+                    if let Some(status) = self.status.as_mut() {
+                        status.push_str(&format!(" ({})", warning));
+                    }
+                }
+                if let Some(profile) = &self.options.platform_profile {
+                    match platform_profile::apply(profile) {
+                        Ok(()) => {
+                            if let Some(status) = self.status.as_mut() {
+                                status.push_str(&tr_args(Msg::PlatformProfileApplied, &[profile]));
+                            }
+                        }
+                        Err(e) => self.error = Some(e),
+                    }
+                }
+            }
+            Err(err) => {
+                self.error = Some(tr_args(Msg::SaveFailed, &[&err]));
+                self.status = None;
+            }
+        }
+    }
+
+    // Applied right away, like charge-type changes on the command line
+    fn toggle_fast_charge(&mut self) {
+        let Some(enabled) = self.fast_charge else {
+            self.status = Some(tr(Msg::FastChargeUnavailable).to_string());
+            return;
+        };
+        if self.options.read_only {
+            self.status = Some(tr(Msg::ReadOnlyRefused).to_string());
+            return;
+        }
+
+        match fast_charge::write(&self.base_path, !enabled) {
+            Ok(()) => {
+                self.fast_charge = fast_charge::read(&self.base_path).ok();
+                let state = if self.fast_charge == Some(true) {
+                    Msg::FastChargeOn
+                } else {
+                    Msg::FastChargeOff
+                };
+                self.status = Some(format!("{}: {}", tr(Msg::FastCharge), tr(state)));
+                self.error = None;
+            }
+            Err(e) => {
+                self.error = Some(tr_args(Msg::FastChargeFailed, &[&e]));
+                self.status = None;
+            }
+        }
+    }
+
+    // Copies the battery's JSON status; without a clipboard (headless, no
+    // display server) it is written to a temp file instead
+    fn copy_status(&mut self) {
+        let report = BatteryReport::from_battery(&self.battery, &self.base_path);
+        let json = match report::battery_json(&report) {
+            Ok(json) => json,
+            Err(err) => {
+                self.error = Some(err);
+                self.status = None;
+                return;
+            }
+        };
+
+        if self.clipboard.is_none() {
+            self.clipboard = Clipboard::new().ok();
+        }
+        let copied = self
+            .clipboard
+            .as_mut()
+            .is_some_and(|clipboard| clipboard.set_text(json.as_str()).is_ok());
+        if copied {
+            self.status = Some(tr(Msg::StatusCopied).to_string());
+            self.error = None;
+            return;
+        }
+
+        let path = std::env::temp_dir().join(format!("batty-{}.json", self.battery.name()));
+        match fs::write(&path, json + "\n") {
+            Ok(_) => {
+                self.status = Some(tr_args(Msg::StatusWritten, &[&path.display()]));
+                self.error = None;
             }
             Err(err) => {
-                self.error = Some(format!("Failed to save thresholds: {}", err));
+                self.error = Some(tr_args(Msg::WriteFailed, &[&path.display(), &err]));
                 self.status = None;
             }
         }
     }
 
     fn select_next_threshold_kind(&mut self) {
+        if !thresholds::manages_start() {
+            return;
+        }
         match self.curr_threshold_kind {
             ThresholdKind::Start => self.curr_threshold_kind = ThresholdKind::End,
             ThresholdKind::End => self.curr_threshold_kind = ThresholdKind::Start,
@@ -151,74 +617,396 @@ impl App {
 
     fn next_tab(&mut self) {
         if self.selected_tab < self.bat_paths.len() - 1 {
-            self.selected_tab += 1;
-            self.base_path = self.bat_paths[self.selected_tab].clone();
-            self.thresholds = Thresholds::load(&self.base_path).unwrap_or_default();
-
-            match Battery::new(&self.base_path) {
-                Ok((battery, warnings)) => {
-                    self.battery = battery;
-                    self.warnings = warnings;
-                    self.status = None;
-                    self.error = None;
-                }
-                Err(e) => {
-                    self.error = Some(format!("Failed to load battery: {}", e));
-                    self.status = None;
-                    self.warnings.clear();
-                }
-            }
+            self.select_tab(self.selected_tab + 1);
         }
     }
 
     fn prev_tab(&mut self) {
         if self.selected_tab > 0 {
-            self.selected_tab -= 1;
-            self.base_path = self.bat_paths[self.selected_tab].clone();
-            self.thresholds = Thresholds::load(&self.base_path).unwrap_or_default();
-
-            match Battery::new(&self.base_path) {
-                Ok((battery, warnings)) => {
-                    self.battery = battery;
-                    self.warnings = warnings;
-                    self.status = None;
-                    self.error = None;
+            self.select_tab(self.selected_tab - 1);
+        }
+    }
+
+    fn footer_line_count(&self) -> usize {
+        usize::from(self.error.is_some())
+            + usize::from(self.status.is_some())
+            + self.warnings.len()
+            + usize::from(self.health_warning().is_some())
+            + self.options.config_warnings.len()
+            + self.write_warnings.len()
+    }
+
+    fn health_warning(&self) -> Option<Warning> {
+        let threshold = self.options.health_warning;
+        let health = self.battery.health()?;
+        (threshold > 0 && health < f32::from(threshold)).then(|| {
+            Warning::warn(format!(
+                "{} health is {:.0}% of its design capacity; consider replacing it.",
+                self.battery.name(),
+                health
+            ))
+        })
+    }
+
+    fn scroll_footer(&mut self, delta: isize) {
+        let max = self.footer_line_count().saturating_sub(FOOTER_LINES);
+        self.footer_scroll = self.footer_scroll.saturating_add_signed(delta).min(max);
+    }
+
+    fn open_picker(&mut self) {
+        if self.bat_paths.len() > 1 {
+            self.picker = Some(self.selected_tab);
+        }
+    }
+
+    fn close_picker(&mut self) {
+        self.picker = None;
+    }
+
+    fn picker_move(&mut self, delta: isize) {
+        if let Some(index) = self.picker {
+            let last = self.bat_paths.len() - 1;
+            self.picker = Some(index.saturating_add_signed(delta).min(last));
+        }
+    }
+
+    fn picker_open_selected(&mut self) {
+        if let Some(index) = self.picker.take() {
+            self.select_tab(index);
+        }
+    }
+
+    fn select_tab(&mut self, index: usize) {
+        self.selected_tab = index;
+        self.base_path = self.bat_paths[self.selected_tab].clone();
+        let loaded = Thresholds::load(&self.base_path).ok();
+        self.active_end = loaded.as_ref().map(|t| t.end);
+        self.thresholds = loaded.unwrap_or_default();
+        self.alarm = alarm::read(&self.base_path).ok();
+        self.charge_type = charge_type::read(&self.base_path).ok();
+        self.fast_charge = fast_charge::read(&self.base_path).ok();
+
+        match Battery::new(&self.base_path) {
+            Ok((battery, warnings)) => {
+                self.was_full = battery.is_full();
+                self.last_full = last_full::load(battery.name());
+                self.full_pulse_frames = 0;
+                self.last_percentage = battery.percentage();
+                self.trend = None;
+                self.percent_samples.clear();
+                if self.show_history {
+                    self.history = history::load(battery.name());
                 }
-                Err(e) => {
-                    self.error = Some(format!("Failed to load battery: {}", e));
-                    self.status = None;
-                    self.warnings.clear();
+                if self.show_details {
+                    self.load_details();
                 }
+                self.battery = battery;
+                self.warnings = warnings;
+                self.status = None;
+                self.error = None;
+            }
+            Err(e) => {
+                self.error = Some(format!("Failed to load battery: {}", e));
+                self.status = None;
+                self.warnings.clear();
             }
         }
     }
-}
 
-fn draw_ui(frame: &mut Frame<'_>, app: &mut App) {
-    match app.battery.refresh() {
-        Ok(warnings) => {
-            app.warnings = warnings;
+    // "Not charging" at (or just below) the end threshold means the limit is doing its job
+    fn charge_limit(&self) -> Option<u8> {
+        let end = self.active_end?;
+        let percentage = self.battery.percentage()?;
+        let limited =
+            self.battery.status == BatteryStatus::NotCharging && percentage + 1.0 >= f32::from(end);
+        limited.then_some(end)
+    }
+
+    fn toggle_history(&mut self) {
+        if !self.options.history {
+            self.status = Some(tr(Msg::HistoryDisabled).to_string());
+            return;
         }
-        Err(e) => {
-            app.error = Some(format!("Failed to refresh battery data: {}", e));
-            app.warnings.clear();
+
+        self.show_history = !self.show_history;
+        self.show_details = false;
+        if self.show_history {
+            self.history = history::load(self.battery.name());
         }
     }
 
+    // Charging changes the readings quickly and a threshold is about to stop
+    // or restart the charge when the level is close to it; Full and "not
+    // charging" barely change at all
+    fn refresh_interval(&self) -> Duration {
+        let Some((fast, idle)) = self.options.adaptive_refresh else {
+            return self.options.refresh_interval;
+        };
+        let near_threshold = self.battery.percentage().is_some_and(|p| {
+            [self.thresholds.start, self.thresholds.end]
+                .iter()
+                .any(|&t| (p - f32::from(t)).abs() <= NEAR_THRESHOLD_MARGIN)
+        });
+        match self.battery.status {
+            BatteryStatus::Charging => fast,
+            BatteryStatus::Full | BatteryStatus::NotCharging => idle,
+            _ if near_threshold => fast,
+            _ => self.options.refresh_interval,
+        }
+    }
+
+    fn toggle_details(&mut self) {
+        self.show_details = !self.show_details;
+        self.show_history = false;
+        if self.show_details {
+            self.load_details();
+        }
+    }
+
+    fn load_details(&mut self) {
+        self.details = battery::read_attributes(&self.base_path)
+            .into_iter()
+            .collect();
+        self.details_scroll = self
+            .details_scroll
+            .min(self.details.len().saturating_sub(1));
+    }
+
+    fn scroll(&mut self, delta: isize) {
+        if self.show_details {
+            let last = self.details.len().saturating_sub(1);
+            self.details_scroll = self.details_scroll.saturating_add_signed(delta).min(last);
+        } else {
+            self.scroll_footer(delta);
+        }
+    }
+
+    fn refresh_if_due(&mut self) {
+        let due = self
+            .last_refresh
+            .is_none_or(|last| last.elapsed() >= self.refresh_interval());
+        if due {
+            self.refresh();
+        }
+    }
+
+    // Re-reads everything now, regardless of the refresh interval
+    fn force_refresh(&mut self) {
+        self.error = None;
+        self.tab_labels_read_at = None;
+        self.refresh();
+        if self.error.is_none() {
+            self.status = Some(tr(Msg::Refreshed).to_string());
+        }
+    }
+
+    // Redraws only when something on screen changed, since with the default
+    // interval a refresh happens on every poll tick
+    fn refresh(&mut self) {
+        let battery = self.battery.clone();
+        let warnings = self.warnings.clone();
+        let power_sources = self.power_sources.clone();
+        let rate = self.displayed_rate();
+        let trend = self.trend;
+        let error = self.error.clone();
+        let full_pulse_frames = self.full_pulse_frames;
+
+        self.last_refresh = Some(Instant::now());
+        match self.battery.refresh() {
+            Ok(warnings) => {
+                self.warnings = warnings;
+                self.track_full_transition();
+                self.track_trend();
+                self.track_power();
+                self.track_rate();
+            }
+            Err(e) => {
+                self.error = Some(format!("Failed to refresh battery data: {}", e));
+                self.warnings.clear();
+            }
+        }
+        if let Some(power_supply_path) = self.base_path.parent() {
+            self.power_sources = ac::find_sources(power_supply_path);
+        }
+        if self.show_details {
+            let details = self.details.clone();
+            self.load_details();
+            self.dirty |= self.details != details;
+        }
+        if self.compare {
+            let compared = self.compared.clone();
+            self.load_compared();
+            self.dirty |= self.compared != compared;
+        }
+
+        self.dirty |= self.battery != battery
+            || self.warnings != warnings
+            || self.power_sources != power_sources
+            || self.displayed_rate() != rate
+            || self.trend != trend
+            || self.error != error
+            || self.full_pulse_frames != full_pulse_frames;
+    }
+
+    // Loads the comparison right away rather than on the next refresh, which
+    // can be half a minute off with the idle interval
+    fn toggle_compare(&mut self) {
+        self.compare = !self.compare;
+        if self.compare {
+            self.load_compared();
+        }
+    }
+
+    fn load_compared(&mut self) {
+        self.compared = self
+            .bat_paths
+            .iter()
+            .filter_map(|path| {
+                let (battery, _) = Battery::new(path).ok()?;
+                Some((battery, Thresholds::load(path).ok()))
+            })
+            .collect();
+    }
+
+    fn track_power(&mut self) {
+        if self.battery.status != self.smoothed_status {
+            self.smoothed_status = self.battery.status.clone();
+            self.smoothed_power = None;
+            self.power_samples.clear();
+            self.percent_samples.clear();
+        }
+
+        let alpha = self.options.power_smoothing;
+        self.smoothed_power = match (self.battery.power_watts(), self.smoothed_power) {
+            (Some(power), Some(average)) => Some(alpha * power + (1.0 - alpha) * average),
+            (power, average) => power.or(average),
+        };
+
+        if let Some(power) = self.battery.power_watts() {
+            if self.power_samples.len() >= self.options.power_samples {
+                self.power_samples.pop_front();
+            }
+            self.power_samples.push_back(power);
+        }
+    }
+
+    // Wattage shown in the Time left box, independent of the smoothing
+    // used for the time estimate
+    fn average_power(&self) -> Option<f32> {
+        if self.power_samples.is_empty() {
+            return None;
+        }
+        Some(self.power_samples.iter().sum::<f32>() / self.power_samples.len() as f32)
+    }
+
+    // Hours until empty (discharging) or full (charging) at the smoothed draw
+    fn time_remaining(&self) -> Option<f32> {
+        let power = self.smoothed_power.filter(|&p| p > 0.0)?;
+        let (now, full) = self.battery.energy_wh()?;
+        match self.battery.status {
+            BatteryStatus::Discharging => Some(now / power),
+            BatteryStatus::Charging => Some((full - now).max(0.0) / power),
+            _ => None,
+        }
+    }
+
+    fn track_rate(&mut self) {
+        let now = Instant::now();
+        while self
+            .percent_samples
+            .front()
+            .is_some_and(|(at, _)| now.duration_since(*at) > RATE_WINDOW)
+        {
+            self.percent_samples.pop_front();
+        }
+        if let Some(percentage) = self.battery.percentage() {
+            self.percent_samples.push_back((now, percentage));
+        }
+    }
+
+    // The rate as shown, in whole %/hr
+    fn displayed_rate(&self) -> Option<i64> {
+        self.percent_rate().map(|rate| rate.round() as i64)
+    }
+
+    // Least-squares slope of the recent charge readings in %/hr. Fitting every
+    // sample instead of the two ends keeps the 1% steps sysfs reports in from
+    // making the value jump.
+    fn percent_rate(&self) -> Option<f32> {
+        if !matches!(
+            self.battery.status,
+            BatteryStatus::Charging | BatteryStatus::Discharging
+        ) {
+            return None;
+        }
+        let (first, _) = *self.percent_samples.front()?;
+        let (last, _) = *self.percent_samples.back()?;
+        if last.duration_since(first) < RATE_MIN_SPAN {
+            return None;
+        }
+
+        let count = self.percent_samples.len() as f32;
+        let points: Vec<(f32, f32)> = self
+            .percent_samples
+            .iter()
+            .map(|(at, percentage)| (at.duration_since(first).as_secs_f32() / 3600.0, *percentage))
+            .collect();
+        let mean_hours = points.iter().map(|(hours, _)| hours).sum::<f32>() / count;
+        let mean_percent = points.iter().map(|(_, percent)| percent).sum::<f32>() / count;
+        let (covariance, variance) =
+            points
+                .iter()
+                .fold((0.0, 0.0), |(covariance, variance), (hours, percent)| {
+                    let dx = hours - mean_hours;
+                    (
+                        covariance + dx * (percent - mean_percent),
+                        variance + dx * dx,
+                    )
+                });
+        (variance > 0.0).then(|| covariance / variance)
+    }
+
+    // Compares against the last distinct reading so the arrow survives
+    // refreshes where sysfs hasn't updated yet; settles on → once idle
+    fn track_trend(&mut self) {
+        let Some(current) = self.battery.percentage() else {
+            self.last_percentage = None;
+            self.trend = None;
+            return;
+        };
+
+        if let Some(last) = self.last_percentage {
+            let idle = !matches!(
+                self.battery.status,
+                BatteryStatus::Charging | BatteryStatus::Discharging
+            );
+            if current != last {
+                self.trend = last.partial_cmp(&current).map(Ordering::reverse);
+            } else if idle {
+                self.trend = Some(Ordering::Equal);
+            }
+        }
+        self.last_percentage = Some(current);
+    }
+
+    fn trend_arrow(&self) -> Option<&'static str> {
+        self.trend.map(|trend| match trend {
+            Ordering::Greater => "↑",
+            Ordering::Less => "↓",
+            Ordering::Equal => "→",
+        })
+    }
+}
+
+fn draw_ui(frame: &mut Frame<'_>, app: &mut App) {
     let show_tabs = app.bat_paths.len() > 1;
-    let has_footer = !app.warnings.is_empty() || app.error.is_some() || app.status.is_some();
+    let footer_line_count = app.footer_line_count();
+    let has_footer = app.show_footer && footer_line_count > 0;
+    let theme = app.options.theme;
 
     // Calculate footer height based on number of lines needed
     let footer_height = if has_footer {
-        let mut lines = 0;
-        if app.error.is_some() {
-            lines += 1;
-        }
-        if app.status.is_some() {
-            lines += 1;
-        }
-        lines += app.warnings.len();
-        (lines.min(3) + 2) as u16 // Add 2 for borders
+        (footer_line_count.min(FOOTER_LINES) + 2) as u16 // Add 2 for borders
     } else {
         0
     };
@@ -251,27 +1039,45 @@ fn draw_ui(frame: &mut Frame<'_>, app: &mut App) {
     };
 
     // Render tabs at very top if multiple batteries
-    if show_tabs {
-        let tab_titles: Vec<String> = app
-            .bat_paths
+    if show_tabs && app.bat_paths.len() > MAX_TABS {
+        let label = &app.tab_labels[app.selected_tab];
+        let current = Paragraph::new(Line::from(vec![
+            Span::styled(
+                format!("{} {}", label.name, label.state),
+                theme.level(label.level).add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!(
+                " ({}/{}) - {}",
+                app.selected_tab + 1,
+                app.bat_paths.len(),
+                tr(Msg::PickerHint)
+                    .replace("{}", &app.options.keymap.describe(Action::BatteryList))
+            )),
+        ]))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(tr(Msg::Batteries)),
+        );
+
+        frame.render_widget(current, main_layout[0]);
+    } else if show_tabs {
+        let tab_titles: Vec<Line> = app
+            .tab_labels
             .iter()
-            .map(|path| {
-                path.file_name()
-                    .and_then(|name| name.to_str())
-                    .unwrap_or("Unknown")
-                    .to_string()
-            })
+            .map(|label| label.line(&theme))
             .collect();
+        let selected_style = theme.level(app.tab_labels[app.selected_tab].level);
 
         let tabs_widget = Tabs::new(tab_titles)
-            .block(Block::default().borders(Borders::ALL).title("Batteries"))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(tr(Msg::Batteries)),
+            )
             .select(app.selected_tab)
             .style(Style::default())
-            .highlight_style(
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD),
-            );
+            .highlight_style(selected_style.add_modifier(Modifier::BOLD));
 
         frame.render_widget(tabs_widget, main_layout[0]);
     }
@@ -283,28 +1089,125 @@ fn draw_ui(frame: &mut Frame<'_>, app: &mut App) {
         main_layout[0]
     };
 
-    // Get battery name for the container title
-    let battery_name = app
-        .base_path
-        .file_name()
-        .and_then(|name| name.to_str())
-        .unwrap_or("Battery");
+    if app.compare {
+        draw_compare(frame, app, battery_container_area);
+    } else {
+        // Get battery name for the container title
+        let battery_name = app
+            .base_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("Battery");
 
-    // Create the main battery container block
-    let battery_block = Block::default()
-        .borders(Borders::ALL)
-        .title(format!(" {} ", battery_name))
-        .title_alignment(Alignment::Center)
-        .style(Style::default());
+        // Create the main battery container block
+        let title = if app.battery.ups {
+            format!(" {} (UPS) ", battery_name)
+        } else {
+            format!(" {} ", battery_name)
+        };
+        let mut battery_block = Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .title_alignment(Alignment::Center)
+            .style(Style::default());
+        if let Some(ac_summary) = ac::summary(&app.power_sources) {
+            battery_block = battery_block.title(
+                Title::from(format!(" {} ", ac_summary))
+                    .position(Position::Bottom)
+                    .alignment(Alignment::Right),
+            );
+        }
 
-    let inner_area = battery_block.inner(battery_container_area);
-    frame.render_widget(battery_block, battery_container_area);
+        let inner_area = battery_block.inner(battery_container_area);
+        frame.render_widget(battery_block, battery_container_area);
+
+        if app.battery.present {
+            draw_battery_details(frame, app, inner_area, show_tabs);
+        } else {
+            let empty = Paragraph::new(tr(Msg::BayEmpty))
+                .style(theme.dim)
+                .centered();
+            frame.render_widget(empty, inner_area);
+        }
+    }
+
+    if let Some(highlighted) = app.picker {
+        draw_picker(frame, app, highlighted, battery_container_area);
+    }
+
+    // Render footer with warnings, errors, and status messages
+    if has_footer {
+        let footer_area = if show_tabs {
+            main_layout[2]
+        } else {
+            main_layout[1]
+        };
+
+        let mut footer_lines = Vec::new();
+
+        if let Some(error) = &app.error {
+            footer_lines.push(Line::from(vec![Span::styled(
+                format!("{}: {}", tr(Msg::Error), error),
+                theme.critical.add_modifier(Modifier::BOLD),
+            )]));
+        }
 
+        if let Some(status) = &app.status {
+            footer_lines.push(Line::from(vec![Span::styled(status.clone(), theme.good)]));
+        }
+
+        let health_warning = app.health_warning();
+        for warning in app
+            .write_warnings
+            .iter()
+            .chain(&app.warnings)
+            .chain(&health_warning)
+            .chain(&app.options.config_warnings)
+        {
+            let (prefix, style) = match warning.severity {
+                Severity::Warning => (tr(Msg::Warning), theme.warning),
+                Severity::Info => (tr(Msg::Note), theme.dim),
+            };
+            footer_lines.push(Line::from(vec![Span::styled(
+                format!("{}: {}", prefix, warning),
+                style,
+            )]));
+        }
+
+        // Messages can disappear between frames, so keep the offset in range
+        let max_scroll = footer_lines.len().saturating_sub(FOOTER_LINES);
+        app.footer_scroll = app.footer_scroll.min(max_scroll);
+
+        let mut footer_block = Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default());
+        if max_scroll > 0 {
+            footer_block = footer_block.title(
+                Title::from(format!(
+                    " {}-{} of {} ({}/{}) ",
+                    app.footer_scroll + 1,
+                    app.footer_scroll + FOOTER_LINES,
+                    footer_lines.len(),
+                    app.options.keymap.describe(Action::ScrollUp),
+                    app.options.keymap.describe(Action::ScrollDown)
+                ))
+                .alignment(Alignment::Right),
+            );
+        }
+        let footer_widget = Paragraph::new(footer_lines)
+            .block(footer_block)
+            .scroll((app.footer_scroll as u16, 0));
+
+        frame.render_widget(footer_widget, footer_area);
+    }
+}
+
+fn draw_battery_details(frame: &mut Frame<'_>, app: &App, area: Rect, show_tabs: bool) {
     // Layout inside the battery container: stats header + configuration
     let inner_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Length(3), Constraint::Min(0)])
-        .split(inner_area);
+        .split(area);
 
     // Header stats layout
     let header_layout = Layout::default()
@@ -313,25 +1216,77 @@ fn draw_ui(frame: &mut Frame<'_>, app: &mut App) {
             Constraint::Fill(1),
             Constraint::Fill(1),
             Constraint::Fill(1),
+            Constraint::Fill(1),
         ])
         .flex(Flex::SpaceAround)
         .split(inner_layout[0]);
 
-    let bat_percent = format!("{:.2}%", app.battery.percentage());
+    let bat_percent = app
+        .battery
+        .percentage()
+        .map(|p| {
+            // Pad to the widest value ("100.00%") so the text doesn't shift as digits change
+            let width = app.options.rounding.format(100.0, None).len();
+            let text = format!("{:>width$}", app.options.rounding.format(p, app.active_end));
+            let text = app.options.full_display.format(&app.battery, p, text);
+            match app.trend_arrow() {
+                Some(arrow) => format!("{} {}", text, arrow),
+                None => text,
+            }
+        })
+        .unwrap_or_else(|| tr(Msg::Unknown).to_string());
+    // Needs a minute of readings first, so "—" stands in until then
+    let rate = app
+        .percent_rate()
+        .map(|rate| format!(" {:+}%/hr ", rate.round() as i64))
+        .unwrap_or_else(|| " — ".to_string());
     let percentage_widget = Paragraph::new(bat_percent)
         .block(
             Block::default()
-                .title("Charge")
+                .title(tr(Msg::Charge))
+                .title(
+                    Title::from(rate)
+                        .position(Position::Bottom)
+                        .alignment(Alignment::Center),
+                )
                 .title_alignment(Alignment::Center)
                 .borders(Borders::ALL),
         )
         .centered();
 
-    let status = app.battery.status.as_str();
+    let status = if app.battery.is_full() {
+        let style = if app.full_pulse_frames % 2 == 1 {
+            app.options.theme.pulse
+        } else {
+            app.options.theme.good
+        };
+        Line::from(Span::styled(
+            format!(
+                "{} {}",
+                BatteryStatus::Full.icon(),
+                app.battery.status.label()
+            ),
+            style,
+        ))
+    } else if let Some(end) = app.charge_limit() {
+        // Explains why charging stopped short of 100%
+        Line::from(format!(
+            "{} {} ({}%)",
+            app.battery.status.icon(),
+            tr(Msg::ChargeLimited),
+            end
+        ))
+    } else {
+        Line::from(format!(
+            "{} {}",
+            app.battery.status.icon(),
+            app.battery.status.label()
+        ))
+    };
     let status_widget = Paragraph::new(status)
         .block(
             Block::default()
-                .title("Status")
+                .title(tr(Msg::Status))
                 .title_alignment(Alignment::Center)
                 .borders(Borders::ALL),
         )
@@ -340,12 +1295,12 @@ fn draw_ui(frame: &mut Frame<'_>, app: &mut App) {
     let cycles = app
         .battery
         .cycles
-        .map(|c| c.to_string())
-        .unwrap_or_else(|| "unknown".to_string());
+        .map(|c| format!("{:>4}", c))
+        .unwrap_or_else(|| tr(Msg::Unknown).to_string());
     let cycles_widget = Paragraph::new(cycles)
         .block(
             Block::default()
-                .title("Cycles")
+                .title(tr(Msg::Cycles))
                 .title_alignment(Alignment::Center)
                 .borders(Borders::ALL),
         )
@@ -355,78 +1310,469 @@ fn draw_ui(frame: &mut Frame<'_>, app: &mut App) {
     frame.render_widget(status_widget, header_layout[1]);
     frame.render_widget(cycles_widget, header_layout[2]);
 
+    let time_left = match (app.time_remaining(), app.average_power()) {
+        (Some(hours), Some(power)) => format!("{} ({:>5.1} W)", format_hours(hours), power),
+        (None, Some(power)) if power > 0.0 => format!("{:>5.1} W", power),
+        _ => tr(Msg::Unknown).to_string(),
+    };
+    let time_title = if app.battery.status == BatteryStatus::Charging {
+        tr(Msg::UntilFull)
+    } else {
+        tr(Msg::TimeLeft)
+    };
+    let time_widget = Paragraph::new(time_left)
+        .block(
+            Block::default()
+                .title(time_title)
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL),
+        )
+        .centered();
+    frame.render_widget(time_widget, header_layout[3]);
+
+    let keys = &app.options.keymap;
+    let mut hints = Vec::new();
+    if show_tabs {
+        hints.push(Line::from(format!(
+            "• {} / {}: {}",
+            keys.describe(Action::PrevTab),
+            keys.describe(Action::NextTab),
+            tr(Msg::HintSwitchTabs)
+        )));
+        hints.push(Line::from(format!(
+            "• {}: {}",
+            keys.describe(Action::BatteryList),
+            tr(Msg::HintBatteryList)
+        )));
+        hints.push(Line::from(format!(
+            "• {}: {}",
+            keys.describe(Action::Compare),
+            tr(Msg::HintCompare)
+        )));
+    }
+    if app.options.history {
+        hints.push(Line::from(format!(
+            "• {}: {}",
+            keys.describe(Action::ToggleHistory),
+            tr(Msg::HintHistory)
+        )));
+    }
+    hints.push(Line::from(format!(
+        "• {}: {}",
+        keys.describe(Action::ToggleDetails),
+        tr(Msg::HintDetails)
+    )));
+    hints.push(Line::from(format!(
+        "• {}: {}",
+        keys.describe(Action::Refresh),
+        tr(Msg::HintRefresh)
+    )));
+    hints.push(Line::from(format!(
+        "• {}: {}",
+        keys.describe(Action::CopyStatus),
+        tr(Msg::HintCopyStatus)
+    )));
+    if app.fast_charge.is_some() {
+        hints.push(Line::from(format!(
+            "• {}: {}",
+            keys.describe(Action::ToggleFastCharge),
+            tr(Msg::HintFastCharge)
+        )));
+    }
+    // Hidden messages are counted so they aren't missed
+    let hidden = app.footer_line_count();
+    hints.push(Line::from(if app.show_footer || hidden == 0 {
+        format!(
+            "• {}: {}",
+            keys.describe(Action::ToggleFooter),
+            tr(Msg::HintToggleFooter)
+        )
+    } else {
+        format!(
+            "• {}: {} ({})",
+            keys.describe(Action::ToggleFooter),
+            tr(Msg::HintToggleFooter),
+            hidden
+        )
+    }));
+
+    // Thresholds don't exist on a UPS, so only the navigation hints remain
+    if app.battery.ups {
+        let mut lines = vec![
+            Line::from(format!("{}.", tr(Msg::UpsNoThresholds))),
+            Line::from(""),
+        ];
+        lines.extend(hints);
+        let widget =
+            Paragraph::new(lines).block(Block::default().title(tr(Msg::Ups)).borders(Borders::ALL));
+        frame.render_widget(widget, inner_layout[1]);
+        return;
+    }
+
     let start_selected = app.curr_threshold_kind == ThresholdKind::Start;
+    let manage_start = thresholds::manages_start();
 
-    let mut lines = vec![
-        Line::from(format_selected(
+    let mut rows = Vec::new();
+    if manage_start {
+        rows.push((
             start_selected,
-            &format!("Start threshold: {}%", app.thresholds.start),
-        )),
-        Line::from(format_selected(
-            !start_selected,
-            &format!("End threshold:   {}%", app.thresholds.end),
-        )),
-        Line::from(""),
-    ];
+            tr(Msg::StartThreshold),
+            format!("{}%", app.thresholds.start),
+        ));
+    }
+    rows.push((
+        !start_selected,
+        tr(Msg::EndThreshold),
+        format!("{}%", app.thresholds.end),
+    ));
+    if let Some(alarm) = app.alarm {
+        rows.push((false, tr(Msg::CapacityAlarm), format!("{}%", alarm)));
+    }
+    if let Some(charge_type) = &app.charge_type {
+        rows.push((false, tr(Msg::ChargeType), charge_type.clone()));
+    }
+    if let Some(enabled) = app.fast_charge {
+        rows.push((
+            false,
+            tr(Msg::FastCharge),
+            tr(if enabled {
+                Msg::FastChargeOn
+            } else {
+                Msg::FastChargeOff
+            })
+            .to_string(),
+        ));
+    }
+    if let Some(serial) = &app.battery.serial_number {
+        rows.push((false, tr(Msg::SerialNumber), serial.clone()));
+    }
+    rows.push((false, tr(Msg::LastFull), last_full::describe(app.last_full)));
+    rows.push((
+        false,
+        tr(Msg::Wear),
+        app.battery
+            .wear_estimate(&app.options.wear)
+            .label()
+            .to_string(),
+    ));
 
-    if show_tabs {
-        lines.push(Line::from("• ←/→ or [/]: switch battery tabs"));
+    // Pad the labels to a common width so the values line up in every language
+    let width = rows
+        .iter()
+        .map(|(_, label, _)| label.chars().count() + 1)
+        .max()
+        .unwrap_or(0);
+    let mut lines: Vec<_> = rows
+        .iter()
+        .map(|(selected, label, value)| {
+            let label = format!("{}:", label);
+            Line::from(format_selected(
+                *selected,
+                &format!("{:<width$} {}", label, value),
+            ))
+        })
+        .collect();
+    lines.push(Line::from(""));
+
+    lines.extend(hints);
+
+    if app.options.read_only {
+        lines.push(Line::from(Span::styled(
+            tr(Msg::ReadOnlyNotice),
+            app.options.theme.warning,
+        )));
+    } else {
+        lines.push(Line::from(format!(
+            "• {} / {}: {}",
+            keys.describe(Action::Increment),
+            keys.describe(Action::Decrement),
+            tr(Msg::HintAdjust)
+        )));
+        // With only the end threshold there is nothing to switch to
+        if manage_start {
+            lines.push(Line::from(format!(
+                "• {}: {}",
+                keys.describe(Action::SwitchThreshold),
+                tr(Msg::HintSelectThreshold)
+            )));
+        }
+        if !app.options.profiles.is_empty() {
+            let profiles: Vec<_> = app
+                .options
+                .profiles
+                .iter()
+                .take(9)
+                .enumerate()
+                .map(|(i, profile)| format!("{} {}", i + 1, profile.name))
+                .collect();
+            lines.push(Line::from(format!(
+                "• {}: {}",
+                profiles.join(", "),
+                tr(Msg::HintLoadProfile)
+            )));
+        }
+        lines.extend_from_slice(&[
+            Line::from(format!(
+                "• {}: {}",
+                keys.describe(Action::Save),
+                tr(Msg::HintSave)
+            )),
+            Line::from(tr(Msg::SaveFailedHelp)),
+        ]);
     }
 
-    lines.extend_from_slice(&[
-        Line::from("• ↑/↓ or +/-: adjust thresholds"),
-        Line::from("• j/k: select threshold"),
-        Line::from("• Enter: save"),
-        Line::from("If saving fails, rerun with sudo or adjust udev permissions."),
-    ]);
+    let config_title = if app.options.read_only {
+        format!(
+            "{} ({})",
+            tr(Msg::ThresholdConfiguration),
+            tr(Msg::ReadOnly)
+        )
+    } else {
+        tr(Msg::ThresholdConfiguration).to_string()
+    };
+    let config_widget =
+        Paragraph::new(lines).block(Block::default().title(config_title).borders(Borders::ALL));
 
-    let config_widget = Paragraph::new(lines).block(
-        Block::default()
-            .title("Threshold Configuration")
-            .borders(Borders::ALL),
-    );
+    if app.show_history {
+        draw_history(frame, app, inner_layout[1]);
+    } else if app.show_details {
+        draw_details(frame, app, inner_layout[1]);
+    } else {
+        frame.render_widget(config_widget, inner_layout[1]);
+    }
+}
 
-    frame.render_widget(config_widget, inner_layout[1]);
+// Narrower columns than this wrap onto another row
+const MIN_COMPARE_COLUMN_WIDTH: u16 = 28;
 
-    // Render footer with warnings, errors, and status messages
-    if has_footer {
-        let footer_area = if show_tabs {
-            main_layout[2]
-        } else {
-            main_layout[1]
-        };
+fn draw_compare(frame: &mut Frame<'_>, app: &App, area: Rect) {
+    let theme = &app.options.theme;
+    let count = app.compared.len().max(1);
+    let per_row = usize::from(area.width / MIN_COMPARE_COLUMN_WIDTH).clamp(1, count);
+    let rows = count.div_ceil(per_row);
 
-        let mut footer_lines = Vec::new();
+    let row_areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Ratio(1, rows as u32); rows])
+        .split(area);
 
-        if let Some(error) = &app.error {
-            footer_lines.push(Line::from(vec![Span::styled(
-                format!("Error: {}", error),
-                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
-            )]));
-        }
+    for (row, chunk) in app.compared.chunks(per_row).enumerate() {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Ratio(1, per_row as u32); per_row])
+            .split(row_areas[row]);
 
-        if let Some(status) = &app.status {
-            footer_lines.push(Line::from(vec![Span::styled(
-                status.clone(),
-                Style::default().fg(Color::Green),
-            )]));
-        }
+        for ((battery, thresholds), column) in chunk.iter().zip(columns.iter()) {
+            let lines = if battery.present {
+                vec![
+                    stat_line(
+                        theme,
+                        tr(Msg::Charge),
+                        battery.percentage().map(|p| format!("{:>5.1}%", p)),
+                    ),
+                    stat_line(
+                        theme,
+                        tr(Msg::Status),
+                        Some(format!(
+                            "{} {}",
+                            battery.status.icon(),
+                            battery.status.label()
+                        )),
+                    ),
+                    stat_line(
+                        theme,
+                        tr(Msg::Health),
+                        battery.health().map(|h| format!("{:>3.0}%", h)),
+                    ),
+                    stat_line(
+                        theme,
+                        tr(Msg::Cycles),
+                        battery.cycles.map(|c| format!("{:>4}", c)),
+                    ),
+                    stat_line(
+                        theme,
+                        tr(Msg::Power),
+                        battery.power_watts().map(|w| format!("{:>5.1} W", w)),
+                    ),
+                    stat_line(
+                        theme,
+                        tr(Msg::Thresholds),
+                        thresholds
+                            .as_ref()
+                            .map(|t| format!("{}%-{}%", t.start, t.end)),
+                    ),
+                ]
+            } else {
+                vec![Line::from(Span::styled(
+                    tr(Msg::BayEmpty),
+                    app.options.theme.dim,
+                ))]
+            };
 
-        for warning in &app.warnings {
-            footer_lines.push(Line::from(vec![Span::styled(
-                format!("Warning: {}", warning),
-                Style::default().fg(Color::Yellow),
-            )]));
+            let widget = Paragraph::new(lines).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!(" {} ", battery.name()))
+                    .title_alignment(Alignment::Center),
+            );
+            frame.render_widget(widget, *column);
         }
+    }
+}
 
-        let footer_widget = Paragraph::new(footer_lines).block(
-            Block::default()
-                .borders(Borders::ALL)
-                .style(Style::default()),
-        );
+fn format_hours(hours: f32) -> String {
+    let minutes = (hours * 60.0).round() as u32;
+    format!("{:>2}h {:02}m", minutes / 60, minutes % 60)
+}
 
-        frame.render_widget(footer_widget, footer_area);
+fn stat_line(theme: &Theme, label: &str, value: Option<String>) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(format!("{:<11}", label), theme.dim),
+        Span::raw(value.unwrap_or_else(|| tr(Msg::Unknown).to_string())),
+    ])
+}
+
+fn draw_picker(frame: &mut Frame<'_>, app: &App, highlighted: usize, area: Rect) {
+    let items: Vec<ListItem> = app
+        .tab_labels
+        .iter()
+        .map(|label| ListItem::new(label.line(&app.options.theme)))
+        .collect();
+    // The arrows, Enter and Esc are fixed while the picker is open
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(
+            tr(Msg::PickerTitle).replace("{}", &app.options.keymap.describe(Action::BatteryList)),
+        ))
+        .highlight_style(app.options.theme.highlight)
+        .highlight_symbol("‣ ");
+
+    let mut state = ListState::default().with_selected(Some(highlighted));
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_details(frame: &mut Frame<'_>, app: &App, area: Rect) {
+    let keys = &app.options.keymap;
+    let block = Block::default()
+        .title(tr_args(
+            Msg::AttributesTitle,
+            &[
+                &keys.describe(Action::ScrollUp),
+                &keys.describe(Action::ScrollDown),
+                &keys.describe(Action::ToggleDetails),
+            ],
+        ))
+        .borders(Borders::ALL);
+
+    let width = app
+        .details
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let lines: Vec<Line> = app
+        .details
+        .iter()
+        .map(|(name, value)| {
+            Line::from(vec![
+                Span::styled(format!("{:<width$} ", name), app.options.theme.dim),
+                Span::raw(value.clone()),
+            ])
+        })
+        .collect();
+    let details = Paragraph::new(lines)
+        .block(block)
+        .scroll((app.details_scroll as u16, 0));
+    frame.render_widget(details, area);
+}
+
+fn draw_history(frame: &mut Frame<'_>, app: &App, area: Rect) {
+    let block = Block::default()
+        .title(
+            tr(Msg::HistoryTitle)
+                .replace("{}", &app.options.keymap.describe(Action::ToggleHistory)),
+        )
+        .borders(Borders::ALL);
+
+    if app.history.len() < 2 {
+        let message = Paragraph::new(tr(Msg::HistoryEmpty)).block(block);
+        frame.render_widget(message, area);
+        return;
     }
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Fill(1), Constraint::Fill(1)])
+        .split(inner);
+
+    let first = app.history[0].timestamp;
+    let days = |timestamp: u64| timestamp.saturating_sub(first) as f64 / 86_400.0;
+    let span = days(app.history[app.history.len() - 1].timestamp).max(1.0);
+
+    let cycles: Vec<(f64, f64)> = app
+        .history
+        .iter()
+        .filter_map(|e| e.cycles.map(|c| (days(e.timestamp), f64::from(c))))
+        .collect();
+    let health: Vec<(f64, f64)> = app
+        .history
+        .iter()
+        .filter_map(|e| e.health.map(|h| (days(e.timestamp), f64::from(h))))
+        .collect();
+
+    let max_cycles = cycles.iter().map(|(_, c)| *c).fold(1.0, f64::max);
+    let min_health = health.iter().map(|(_, h)| *h).fold(100.0, f64::min);
+
+    frame.render_widget(
+        history_chart(
+            tr(Msg::Cycles).to_string(),
+            &cycles,
+            span,
+            [0.0, max_cycles * 1.1],
+            app.options.theme.accent,
+        ),
+        layout[0],
+    );
+    frame.render_widget(
+        history_chart(
+            format!("{} %", tr(Msg::Health)),
+            &health,
+            span,
+            [(min_health - 5.0).max(0.0), 100.0],
+            app.options.theme.good,
+        ),
+        layout[1],
+    );
+}
+
+fn history_chart<'a>(
+    title: String,
+    data: &'a [(f64, f64)],
+    span: f64,
+    y_bounds: [f64; 2],
+    style: Style,
+) -> Chart<'a> {
+    let dataset = Dataset::default()
+        .marker(Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(style)
+        .data(data);
+
+    Chart::new(vec![dataset])
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .x_axis(
+            Axis::default()
+                .title(tr(Msg::Days))
+                .bounds([0.0, span])
+                .labels(vec![Span::raw("0"), Span::raw(format!("{:.0}", span))]),
+        )
+        .y_axis(Axis::default().bounds(y_bounds).labels(vec![
+            Span::raw(format!("{:.0}", y_bounds[0])),
+            Span::raw(format!("{:.0}", y_bounds[1])),
+        ]))
 }
 
 fn format_selected(selected: bool, text: &str) -> String {
@@ -436,3 +1782,157 @@ fn format_selected(selected: bool, text: &str) -> String {
         format!("  {}", text)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config, test_support::MockTree, theme::ThemeName};
+
+    fn options() -> TuiOptions {
+        TuiOptions {
+            animations: false,
+            history: false,
+            min_end: None,
+            rounding: Rounding::default(),
+            full_display: FullDisplay::default(),
+            keymap: KeyMap::default(),
+            config_warnings: Vec::new(),
+            read_only: false,
+            compare: false,
+            power_smoothing: config::DEFAULT_POWER_SMOOTHING,
+            power_samples: config::DEFAULT_POWER_SAMPLES,
+            refresh_interval: config::DEFAULT_REFRESH_INTERVAL,
+            adaptive_refresh: None,
+            health_warning: config::DEFAULT_HEALTH_WARNING,
+            profiles: Vec::new(),
+            platform_profile: None,
+            wear: WearCutoffs::default(),
+            hide_footer: false,
+            theme: Theme::new(ThemeName::default()),
+        }
+    }
+
+    fn battery(tree: &MockTree, name: &str, start: &str, end: &str) -> PathBuf {
+        tree.battery(
+            name,
+            &[
+                ("energy_now", "30000000"),
+                ("energy_full", "50000000"),
+                ("status", "Discharging"),
+                ("charge_control_start_threshold", start),
+                ("charge_control_end_threshold", end),
+            ],
+        )
+    }
+
+    fn app(tree: &MockTree, start: &str, end: &str) -> App {
+        App::new(vec![battery(tree, "BAT0", start, end)], options()).unwrap()
+    }
+
+    #[test]
+    fn increment_clamps_at_100() {
+        let tree = MockTree::new();
+        let mut app = app(&tree, "40", "99");
+        app.handle_key(KeyCode::Char('j'));
+        assert_eq!(app.curr_threshold_kind, ThresholdKind::End);
+        for _ in 0..3 {
+            assert!(app.handle_key(KeyCode::Up));
+        }
+        assert_eq!(app.thresholds.end, 100);
+        assert_eq!(app.error, None);
+    }
+
+    #[test]
+    fn decrement_saturates_at_0() {
+        let tree = MockTree::new();
+        let mut app = app(&tree, "1", "80");
+        assert_eq!(app.curr_threshold_kind, ThresholdKind::Start);
+        for _ in 0..3 {
+            app.handle_key(KeyCode::Down);
+        }
+        assert_eq!(app.thresholds.start, 0);
+        assert_eq!(app.error, None);
+    }
+
+    #[test]
+    fn start_stays_below_end() {
+        let tree = MockTree::new();
+        let mut app = app(&tree, "79", "80");
+        app.handle_key(KeyCode::Up);
+        assert_eq!(app.thresholds.start, 79);
+        assert!(app.error.is_some());
+
+        app.handle_key(KeyCode::Char('j'));
+        app.handle_key(KeyCode::Down);
+        assert_eq!(app.thresholds.end, 80);
+        assert!(app.error.is_some());
+    }
+
+    #[test]
+    fn refresh_redraws_only_on_change() {
+        let tree = MockTree::new();
+        let mut app = app(&tree, "40", "80");
+        app.refresh();
+        app.dirty = false;
+
+        app.refresh();
+        assert!(!app.dirty);
+
+        tree.write(&app.base_path, "energy_now", "29000000\n");
+        app.refresh();
+        assert!(app.dirty);
+    }
+
+    #[test]
+    fn full_pulse_counts_drawn_frames() {
+        let tree = MockTree::new();
+        let mut app = app(&tree, "40", "80");
+        app.options.animations = true;
+        tree.write(&app.base_path, "status", "Full\n");
+        app.refresh();
+        assert_eq!(app.full_pulse_frames, FULL_PULSE_FRAMES);
+
+        // Further refreshes leave it alone; only drawn frames count it down
+        app.refresh();
+        assert_eq!(app.full_pulse_frames, FULL_PULSE_FRAMES);
+        for _ in 0..FULL_PULSE_FRAMES {
+            app.dirty = false;
+            app.advance_pulse();
+            assert!(app.dirty);
+        }
+        app.dirty = false;
+        app.advance_pulse();
+        assert_eq!(app.full_pulse_frames, 0);
+        assert!(!app.dirty);
+    }
+
+    #[test]
+    fn compare_loads_immediately() {
+        let tree = MockTree::new();
+        let bat0 = battery(&tree, "BAT0", "40", "80");
+        let bat1 = battery(&tree, "BAT1", "60", "90");
+        let mut app = App::new(vec![bat0, bat1], options()).unwrap();
+        assert!(app.compared.is_empty());
+
+        app.handle_key(KeyCode::Char('c'));
+        assert!(app.compare);
+        assert_eq!(app.compared.len(), 2);
+    }
+
+    #[test]
+    fn select_tab_updates_base_path() {
+        let tree = MockTree::new();
+        let bat0 = battery(&tree, "BAT0", "40", "80");
+        let bat1 = battery(&tree, "BAT1", "60", "90");
+        let mut app = App::new(vec![bat0.clone(), bat1.clone()], options()).unwrap();
+        assert_eq!(app.base_path, bat0);
+
+        app.handle_key(KeyCode::Right);
+        assert_eq!(app.selected_tab, 1);
+        assert_eq!(app.base_path, bat1);
+        assert_eq!((app.thresholds.start, app.thresholds.end), (60, 90));
+
+        app.handle_key(KeyCode::Left);
+        assert_eq!(app.base_path, bat0);
+    }
+}
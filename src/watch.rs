@@ -0,0 +1,442 @@
+use crate::{
+    ac,
+    battery::{Battery, BatteryStatus},
+    color::{self, Color},
+    signals,
+    thresholds::{ThresholdKind, Thresholds},
+};
+use serde::Serialize;
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, Instant, SystemTime},
+};
+
+const CSV_HEADER: &str = "timestamp,battery,percentage,power_w,status,temperature_c";
+// The critical action re-arms once the charge climbs this far above the
+// critical level (or the battery charges), so jitter around it can't retrigger
+const CRITICAL_REARM_MARGIN: f32 = 2.0;
+
+// Charge has to move this far past a threshold before a crossing is reported,
+// and this far from the last reported level before a percentage event
+const THRESHOLD_HYSTERESIS: f32 = 0.5;
+const PERCENTAGE_EVENT_STEP: f32 = 5.0;
+
+pub struct CriticalAction {
+    pub percent: u8,
+    pub command: String,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Output {
+    Text,
+    // One JSON line per change (plus an initial sample)
+    JsonEvents,
+    // One JSON sample line per interval
+    JsonSamples,
+}
+
+#[derive(Serialize)]
+struct Event<'a> {
+    timestamp: &'a str,
+    battery: &'a str,
+    #[serde(flatten)]
+    kind: EventKind,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum EventKind {
+    Sample {
+        percentage: Option<f32>,
+        status: &'static str,
+        power_w: Option<f32>,
+    },
+    Status {
+        from: &'static str,
+        to: &'static str,
+        percentage: Option<f32>,
+    },
+    Threshold {
+        threshold: String,
+        value: u8,
+        direction: &'static str,
+        percentage: f32,
+    },
+    Percentage {
+        from: f32,
+        to: f32,
+    },
+}
+
+// Remembers what was last reported so only changes produce events
+#[derive(Default)]
+struct EventState {
+    status: Option<BatteryStatus>,
+    reported_percentage: Option<f32>,
+    // Whether the charge is above each threshold; None until first seen
+    above_start: Option<bool>,
+    above_end: Option<bool>,
+}
+
+impl EventState {
+    fn update(&mut self, battery: &Battery, thresholds: Option<&Thresholds>) -> Vec<EventKind> {
+        let mut events = Vec::new();
+        let percentage = battery.percentage().map(round);
+
+        if self.status.is_none() {
+            events.push(sample(battery));
+        } else if self.status.as_ref() != Some(&battery.status) {
+            events.push(EventKind::Status {
+                from: self
+                    .status
+                    .as_ref()
+                    .map(|s| s.as_str())
+                    .unwrap_or("unknown"),
+                to: battery.status.as_str(),
+                percentage,
+            });
+        }
+        self.status = Some(battery.status.clone());
+
+        let Some(percentage) = percentage else {
+            return events;
+        };
+
+        if let Some(thresholds) = thresholds {
+            for kind in [ThresholdKind::Start, ThresholdKind::End] {
+                let value = thresholds.get(kind);
+                // A start threshold of 0 means the battery has none
+                if kind == ThresholdKind::Start && value == 0 {
+                    continue;
+                }
+                let side = match kind {
+                    ThresholdKind::Start => &mut self.above_start,
+                    ThresholdKind::End => &mut self.above_end,
+                };
+                if let Some(above) = crossing(side, f32::from(value), percentage) {
+                    events.push(EventKind::Threshold {
+                        threshold: kind.to_string(),
+                        value,
+                        direction: if above { "above" } else { "below" },
+                        percentage,
+                    });
+                }
+            }
+        }
+
+        match self.reported_percentage {
+            Some(from) if (percentage - from).abs() >= PERCENTAGE_EVENT_STEP => {
+                events.push(EventKind::Percentage {
+                    from,
+                    to: percentage,
+                });
+                self.reported_percentage = Some(percentage);
+            }
+            Some(_) => {}
+            None => self.reported_percentage = Some(percentage),
+        }
+
+        events
+    }
+}
+
+// Returns the new side when the charge has clearly moved across the threshold
+fn crossing(side: &mut Option<bool>, threshold: f32, percentage: f32) -> Option<bool> {
+    let Some(above) = *side else {
+        *side = Some(percentage >= threshold);
+        return None;
+    };
+    let now_above = if percentage >= threshold + THRESHOLD_HYSTERESIS {
+        true
+    } else if percentage <= threshold - THRESHOLD_HYSTERESIS {
+        false
+    } else {
+        above
+    };
+    *side = Some(now_above);
+    (now_above != above).then_some(now_above)
+}
+
+fn round(percentage: f32) -> f32 {
+    (percentage * 100.0).round() / 100.0
+}
+
+fn sample(battery: &Battery) -> EventKind {
+    EventKind::Sample {
+        percentage: battery.percentage().map(round),
+        status: battery.status.as_str(),
+        power_w: battery.power_watts().map(round),
+    }
+}
+
+fn print_event(timestamp: &str, battery: &Battery, kind: EventKind) -> Result<(), String> {
+    let event = Event {
+        timestamp,
+        battery: battery.name(),
+        kind,
+    };
+    let line =
+        serde_json::to_string(&event).map_err(|e| format!("Failed to serialize event: {}", e))?;
+    println!("{}", line);
+    Ok(())
+}
+
+// Per-battery state carried between ticks
+#[derive(Default)]
+struct Watched {
+    critical_fired: bool,
+    events: EventState,
+    // None until the first tick, so starting on AC doesn't count as connecting
+    ac_online: Option<bool>,
+}
+
+pub fn run(
+    battery_paths: &[PathBuf],
+    interval: Duration,
+    csv_path: Option<&Path>,
+    critical: Option<&CriticalAction>,
+    output: Output,
+    reapply: Option<&Thresholds>,
+) -> Result<(), String> {
+    let mut csv = csv_path
+        .map(|path| open_csv(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e)))
+        .transpose()?;
+    let mut watched: Vec<Watched> = battery_paths.iter().map(|_| Watched::default()).collect();
+    let name_width = battery_paths
+        .iter()
+        .filter_map(|path| path.file_name())
+        .map(|name| name.len())
+        .max()
+        .unwrap_or(0);
+
+    loop {
+        let timestamp = humantime::format_rfc3339_seconds(SystemTime::now()).to_string();
+
+        for (battery_path, state) in battery_paths.iter().zip(watched.iter_mut()) {
+            // A transient read error skips this battery for one tick rather than ending the watch
+            let battery = match Battery::new(battery_path) {
+                Ok((battery, _)) => battery,
+                Err(e) => {
+                    eprintln!(
+                        "{} Failed to read battery {}: {}",
+                        timestamp,
+                        battery_path.display(),
+                        e
+                    );
+                    continue;
+                }
+            };
+
+            match output {
+                Output::Text => {
+                    let percentage = battery
+                        .percentage()
+                        .map(|p| color::paint(&format!("{:.2}%", p), Color::for_percentage(p)))
+                        .unwrap_or_else(|| "unknown".to_string());
+                    println!(
+                        "{} {:<name_width$} {} {}",
+                        timestamp,
+                        battery.name(),
+                        percentage,
+                        battery.status.label()
+                    );
+                }
+                Output::JsonSamples => print_event(&timestamp, &battery, sample(&battery))?,
+                Output::JsonEvents => {
+                    // Thresholds are re-read every tick so changes made elsewhere count
+                    let thresholds = Thresholds::load(battery_path).ok();
+                    for kind in state.events.update(&battery, thresholds.as_ref()) {
+                        print_event(&timestamp, &battery, kind)?;
+                    }
+                }
+            }
+
+            if let Some(file) = csv.as_mut() {
+                write_csv_row(file, &timestamp, &battery)
+                    .map_err(|e| format!("Failed to write CSV row: {}", e))?;
+            }
+
+            if let (Some(action), Some(percentage)) = (critical, battery.percentage()) {
+                let critical_level = f32::from(action.percent);
+                if battery.status == BatteryStatus::Charging
+                    || percentage >= critical_level + CRITICAL_REARM_MARGIN
+                {
+                    state.critical_fired = false;
+                } else if !state.critical_fired && percentage <= critical_level {
+                    state.critical_fired = true;
+                    run_critical_command(action, &battery, percentage, &timestamp);
+                }
+            }
+
+            if let Some(thresholds) = reapply {
+                // AC adapters live next to the battery in the power supply directory
+                let sources = battery_path
+                    .parent()
+                    .map(ac::find_sources)
+                    .unwrap_or_default();
+                let online = ac::active_source(&sources).is_some();
+                if state.ac_online == Some(false) && online {
+                    reapply_thresholds(battery_path, &battery, thresholds, &timestamp);
+                }
+                state.ac_online = Some(online);
+            }
+        }
+
+        // A blank line between ticks keeps each round of batteries together
+        if output == Output::Text && battery_paths.len() > 1 {
+            println!();
+        }
+
+        if !signals::sleep(interval) {
+            return Ok(());
+        }
+    }
+}
+
+// Failures are logged rather than ending the watch loop
+fn run_critical_command(
+    action: &CriticalAction,
+    battery: &Battery,
+    percentage: f32,
+    timestamp: &str,
+) {
+    eprintln!(
+        "{} {} at {:.2}% (critical level {}%), running: {}",
+        timestamp,
+        battery.name(),
+        percentage,
+        action.percent,
+        action.command
+    );
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(&action.command)
+        .env("BATTY_BATTERY", battery.name())
+        .env("BATTY_PERCENT", format!("{:.0}", percentage))
+        .status();
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => eprintln!("Warning: critical_command exited with {}", status),
+        Err(e) => eprintln!("Warning: failed to run critical_command: {}", e),
+    }
+}
+
+// Some firmware resets the thresholds on replug. Failures are logged and the
+// next connection tries again.
+fn reapply_thresholds(
+    battery_path: &Path,
+    battery: &Battery,
+    thresholds: &Thresholds,
+    timestamp: &str,
+) {
+    match thresholds.save(battery_path) {
+        Ok(()) => eprintln!(
+            "{} {} AC connected, re-applied thresholds {}%-{}%",
+            timestamp,
+            battery.name(),
+            thresholds.start,
+            thresholds.end
+        ),
+        Err(e) => eprintln!(
+            "{} {} AC connected, failed to re-apply thresholds: {}",
+            timestamp,
+            battery.name(),
+            e
+        ),
+    }
+}
+
+pub enum WaitOutcome {
+    Reached,
+    TimedOut,
+    Interrupted,
+}
+
+// Polls until the charge is at/above (or at/below with `below`) the target
+pub fn wait_until(
+    battery_path: &Path,
+    target: u8,
+    below: bool,
+    interval: Duration,
+    timeout: Option<Duration>,
+    quiet: bool,
+) -> Result<WaitOutcome, String> {
+    let (mut battery, _) =
+        Battery::new(battery_path).map_err(|e| format!("Failed to read battery: {}", e))?;
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let target_f = f32::from(target);
+
+    loop {
+        let percentage = battery
+            .percentage()
+            .ok_or_else(|| format!("Charge level of {} is unknown", battery.name()))?;
+        let reached = if below {
+            percentage <= target_f
+        } else {
+            percentage >= target_f
+        };
+        if !quiet {
+            eprintln!(
+                "{}: {:.2}% (waiting for {}{}%)",
+                battery.name(),
+                percentage,
+                if below { "<=" } else { ">=" },
+                target
+            );
+        }
+        if reached {
+            return Ok(WaitOutcome::Reached);
+        }
+
+        let sleep_for = match deadline {
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    return Ok(WaitOutcome::TimedOut);
+                }
+                interval.min(remaining)
+            }
+            None => interval,
+        };
+        if !signals::sleep(sleep_for) {
+            return Ok(WaitOutcome::Interrupted);
+        }
+
+        battery
+            .refresh()
+            .map_err(|e| format!("Failed to read battery: {}", e))?;
+    }
+}
+
+fn open_csv(path: &Path) -> io::Result<File> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if file.metadata()?.len() == 0 {
+        writeln!(file, "{}", CSV_HEADER)?;
+    }
+    Ok(file)
+}
+
+// Unknown values are left empty so spreadsheets treat them as missing
+fn write_csv_row(file: &mut File, timestamp: &str, battery: &Battery) -> io::Result<()> {
+    let optional = |value: Option<f32>, precision: usize| {
+        value
+            .map(|v| format!("{:.*}", precision, v))
+            .unwrap_or_default()
+    };
+
+    writeln!(
+        file,
+        "{},{},{},{},{},{}",
+        timestamp,
+        battery.name(),
+        optional(battery.percentage(), 2),
+        optional(battery.power_watts(), 2),
+        battery.status.as_str(),
+        optional(battery.temperature, 1)
+    )?;
+    // Flush every row so a crash or kill loses at most the current sample
+    file.flush()
+}